@@ -1,11 +1,11 @@
 use crate::{
     config::{SystemRequirementsChecker, TSystemRequirementsChecker, DOCKER_REQUIREMENT, GIT_REQUIREMENT},
-    infra::console::{print_error, style_spinner},
+    infra::console::{print_error, print_info, style_spinner},
 };
 use colored::*;
 use indicatif::{HumanDuration, ProgressBar};
 use opraas_core::{
-    application::build::{ArtifactBuilderService, TArtifactBuilderService},
+    application::build::{ArtifactBuilderService, BuildOutcome, TArtifactBuilderService},
     config::CoreConfig,
     domain::{ArtifactFactory, ArtifactKind, ProjectFactory, TArtifactFactory, TProjectFactory},
     infra::artifact::{DockerArtifactRepository, GitArtifactSourceRepository},
@@ -26,6 +26,7 @@ pub enum BuildTargets {
     Contracts,
     Proposer,
     Geth,
+    Explorer,
     All,
 }
 
@@ -44,12 +45,17 @@ impl BuildCommand {
         }
     }
 
-    pub fn run(&self, target: BuildTargets) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn run(
+        &self,
+        target: BuildTargets,
+        platforms: Vec<String>,
+        force: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         self.system_requirements_checker
             .check(vec![GIT_REQUIREMENT, DOCKER_REQUIREMENT])?;
 
         let project = self.project_factory.from_cwd().unwrap();
-        let config = CoreConfig::new_from_toml(&project.config).unwrap();
+        let config = CoreConfig::new_from_toml(&project.config)?;
 
         // assemble list of artifacts to build
         let artifacts = match target {
@@ -69,8 +75,15 @@ impl BuildCommand {
             BuildTargets::Geth => vec![self
                 .artifacts_factory
                 .get(&ArtifactKind::Geth, &project, &config)],
+            BuildTargets::Explorer => vec![self
+                .artifacts_factory
+                .get(&ArtifactKind::Explorer, &project, &config)],
         };
 
+        if platforms.len() > 1 {
+            print_info("Multi-platform images can't be loaded into docker, they stay in the buildx cache until release pushes them with the same --platform");
+        }
+
         // start time count and spinner
         let started = Instant::now();
         let build_spinner = style_spinner(
@@ -91,24 +104,25 @@ impl BuildCommand {
             .map(|&ref artifact| {
                 let artifact = Arc::clone(artifact); // Clone the Arc for thread ownership
                 let builder_service = Arc::clone(&self.artifacts_builder);
+                let platforms = platforms.clone();
 
-                thread::spawn(move || -> Result<(), String> {
-                    match builder_service.build(&artifact) {
-                        Ok(_) => {}
+                thread::spawn(move || -> Result<(String, BuildOutcome), String> {
+                    match builder_service.build(&artifact, &platforms, force) {
+                        Ok(outcome) => Ok((artifact.to_string(), outcome)),
                         Err(e) => {
                             print_error(&format!("❌ Error building {}", artifact));
-                            return Err(e.to_string());
+                            Err(e.to_string())
                         }
                     }
-                    Ok(())
                 })
             })
             .collect();
 
         // Wait for all threads to complete
+        let mut outcomes = vec![];
         for handle in handles {
             match handle.join() {
-                Ok(Ok(_)) => {}
+                Ok(Ok(outcome)) => outcomes.push(outcome),
                 Ok(Err(e)) => return Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, e))),
                 Err(_) => {
                     return Err(Box::new(std::io::Error::new(
@@ -120,6 +134,12 @@ impl BuildCommand {
         }
 
         build_spinner.finish_with_message(format!("✔️ Built in {}", HumanDuration(started.elapsed())));
+        for (artifact, outcome) in outcomes {
+            match outcome {
+                BuildOutcome::Built => println!("  {} {}", artifact, "built".green()),
+                BuildOutcome::Cached => println!("  {} {}", artifact, "cached, sources unchanged".dimmed()),
+            }
+        }
 
         // print instructions
 
@@ -0,0 +1,57 @@
+use crate::infra::console::{print_success, print_warning, unlock_keystores, Dialoguer, TDialoguer};
+use clap::Subcommand;
+use opraas_core::{
+    config::{ConfigValidator, CoreConfig, TConfigValidator},
+    domain::{ProjectFactory, TProjectFactory},
+};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigSubcommand {
+    /// Check config.toml for invalid values before deploying
+    Validate,
+}
+
+pub struct ConfigCommand {
+    config_validator: Box<dyn TConfigValidator>,
+    project_factory: Box<dyn TProjectFactory>,
+    dialoguer: Box<dyn TDialoguer>,
+}
+
+// implementations ================================================
+
+impl ConfigCommand {
+    pub fn new() -> Self {
+        Self {
+            config_validator: Box::new(ConfigValidator::new()),
+            project_factory: Box::new(ProjectFactory::new()),
+            dialoguer: Box::new(Dialoguer::new()),
+        }
+    }
+
+    pub fn run(&self, cmd: ConfigSubcommand) -> Result<(), Box<dyn std::error::Error>> {
+        match cmd {
+            ConfigSubcommand::Validate => self.validate(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let project = self
+            .project_factory
+            .from_cwd()
+            .ok_or("Not inside a project")?;
+        unlock_keystores(self.dialoguer.as_ref(), &project.root.join("keystore"))?;
+        let mut config = CoreConfig::new_from_toml(&project.config)?;
+        config.accounts.resolve()?;
+
+        self.config_validator.validate(&config)?;
+        for roles in config.accounts.reused_keys() {
+            print_warning(&format!(
+                "{} share the same private key, that's fine for dev but use a different one for each role anywhere else",
+                roles.join(", ")
+            ));
+        }
+        print_success("✔️ config.toml is valid");
+
+        Ok(())
+    }
+}
@@ -5,7 +5,10 @@ use crate::{
         SystemRequirementsChecker, TSystemRequirementsChecker, DOCKER_REQUIREMENT, HELM_REQUIREMENT, K8S_REQUIREMENT,
         TERRAFORM_REQUIREMENT,
     },
-    infra::console::{print_info, style_spinner, Dialoguer, TDialoguer},
+    infra::console::{
+        print_info, print_warning, prompt_release_name, require_inputs, style_spinner, unlock_keystores, Dialoguer,
+        TDialoguer, REGISTRY_INPUT, RELEASE_INPUT, YES_INPUT,
+    },
 };
 use clap::ValueEnum;
 use colored::*;
@@ -13,14 +16,21 @@ use indicatif::ProgressBar;
 use log::info;
 use opraas_core::{
     application::{
+        catalog::{ReleaseCatalogService, TReleaseCatalogService},
         contracts::{deploy::{StackContractsDeployerService, TStackContractsDeployerService}, StackContractsInspectorService, TStackContractsInspectorService},
         stack::{deploy::{StackInfraDeployerService, TStackInfraDeployerService}, StackInfraInspectorService, TStackInfraInspectorService},
     },
-    config::CoreConfig,
-    domain::{ArtifactFactory, ArtifactKind, ProjectFactory, Release, Stack, TArtifactFactory, TProjectFactory},
+    config::{ConfigValidator, CoreConfig, TConfigValidator},
+    domain::{
+        ArtifactFactory, ArtifactKind, Project, ProjectFactory, Release, Stack, TArtifactFactory, TProjectFactory,
+        TReleaseManifestRepository,
+    },
+    helm,
     infra::{
         deployment::InMemoryDeploymentRepository,
-        release::{DockerReleaseRepository, DockerReleaseRunner},
+        release::{
+            DockerReleaseRepository, DockerReleaseRunner, InMemoryReleaseManifestRepository, OciReleaseRegistry,
+        },
         stack::{deployer_terraform::TerraformDeployer, repo_inmemory::GitStackInfraRepository},
     },
 };
@@ -38,7 +48,9 @@ pub struct DeployCommand {
     contracts_inspector: Box<dyn TStackContractsInspectorService>,
     infra_deployer: Box<dyn TStackInfraDeployerService>,
     infra_inspector: Box<dyn TStackInfraInspectorService>,
+    release_catalog: Box<dyn TReleaseCatalogService>,
     system_requirement_checker: Box<dyn TSystemRequirementsChecker>,
+    config_validator: Box<dyn TConfigValidator>,
     artifacts_factory: Box<dyn TArtifactFactory>,
     project_factory: Box<dyn TProjectFactory>,
 }
@@ -54,7 +66,7 @@ impl DeployCommand {
             dialoguer: Box::new(Dialoguer::new()),
             contracts_deployer: Box::new(StackContractsDeployerService::new(
                 Box::new(InMemoryDeploymentRepository::new(&project.root)),
-                Box::new(DockerReleaseRepository::new()),
+                Box::new(DockerReleaseRepository::new(&project.releases)),
                 Box::new(DockerReleaseRunner::new()),
             )),
             contracts_inspector: Box::new(StackContractsInspectorService::new()),
@@ -64,7 +76,11 @@ impl DeployCommand {
                 Box::new(InMemoryDeploymentRepository::new(&project.root)),
             )),
             infra_inspector: Box::new(StackInfraInspectorService::new()),
+            release_catalog: Box::new(ReleaseCatalogService::new(Box::new(
+                OciReleaseRegistry::new(),
+            ))),
             system_requirement_checker: Box::new(SystemRequirementsChecker::new()),
+            config_validator: Box::new(ConfigValidator::new()),
             artifacts_factory: Box::new(ArtifactFactory::new()),
             project_factory,
         }
@@ -75,6 +91,9 @@ impl DeployCommand {
         target: DeployTarget,
         name: String,
         deploy_deterministic_deployer: bool,
+        registry_url: Option<String>,
+        release_name: Option<String>,
+        yes: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.system_requirement_checker.check(vec![
             DOCKER_REQUIREMENT,
@@ -84,7 +103,16 @@ impl DeployCommand {
         ])?;
 
         let project = self.project_factory.from_cwd().unwrap();
-        let config = CoreConfig::new_from_toml(&project.config).unwrap();
+        unlock_keystores(self.dialoguer.as_ref(), &project.root.join("keystore"))?;
+        let mut config = CoreConfig::new_from_toml(&project.config)?;
+        config.accounts.resolve()?;
+        self.config_validator.validate(&config)?;
+        for roles in config.accounts.reused_keys() {
+            print_warning(&format!(
+                "{} share the same private key, use a different one for each role",
+                roles.join(", ")
+            ));
+        }
 
         // dev is reserved for local deployments
         if name == "dev" {
@@ -93,12 +121,55 @@ impl DeployCommand {
             return Err("Name cannot contain spaces".into());
         }
 
-        let registry_url: String = self
-            .dialoguer
-            .prompt("Input Docker registry url (e.g. dockerhub.io/wakeuplabs) ");
-        let release_name: String = self.dialoguer.prompt("Input release name (e.g. v0.1.0)");
+        require_inputs(
+            self.dialoguer.as_ref(),
+            vec![
+                (REGISTRY_INPUT, registry_url.is_some()),
+                (RELEASE_INPUT, release_name.is_some()),
+                (YES_INPUT, yes),
+            ],
+        )?;
+
+        let registry_url: String = registry_url.unwrap_or_else(|| {
+            self.dialoguer
+                .prompt("Input Docker registry url (e.g. dockerhub.io/wakeuplabs) ")
+        });
+        let release_name: String = release_name.unwrap_or_else(|| {
+            prompt_release_name(
+                self.dialoguer.as_ref(),
+                self.release_catalog.as_ref(),
+                &registry_url,
+            )
+        });
+
+        // pre-flight, every image the deployment runs must be in the registry before anything is spent =========
+
+        let preflight_spinner = style_spinner(ProgressBar::new_spinner(), "Checking release images...");
+        let releases = self.releases(
+            &target,
+            &name,
+            &project,
+            &config,
+            &registry_url,
+            &release_name,
+        )?;
+        let missing = self.release_catalog.missing(&releases);
+        preflight_spinner.finish_and_clear();
+
+        let missing = missing?;
+        if !missing.is_empty() {
+            return Err(format!(
+                "Images missing in the registry, release them before deploying:\n{}",
+                missing
+                    .iter()
+                    .map(|release| format!("  - {}", release.pinned_uri()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+            .into());
+        }
 
-        if !self
+        if !yes && !self
             .dialoguer
             .confirm("This may involve some costs. Have you double-checked the configuration? Please review .env, config.toml, infra/helm/values.yaml to ensure it's what you expect. Help yourself with the README.md files if in doubt.")
         {
@@ -116,6 +187,11 @@ impl DeployCommand {
                     .get(&ArtifactKind::Contracts, &project, &config),
                 &release_name,
                 &registry_url,
+            )
+            .pinned_by(
+                InMemoryReleaseManifestRepository::new(&project.releases)
+                    .find(&release_name)?
+                    .as_ref(),
             );
             self.contracts_deployer.deploy(
                 &name,
@@ -185,13 +261,72 @@ impl DeployCommand {
             Some useful commands for you now:\n\n\
             - {bin_name} {command}\n\
             \tDisplay the artifacts for each deployment.\n\n\
-            {note}\n",
+            - {bin_name} {upgrade_command}\n\
+            \tRoll the deployment to a new release.\n\n\
+            - {bin_name} {destroy_command}\n\
+            \tTear down the deployment infra and archive its records.\n",
             title = "What's Next?".bright_white().bold(),
             bin_name=env!("CARGO_BIN_NAME").blue(),
             command="inspect [contracts|infra|all] --name <deployment_name>".blue(),
-            note="NOTE: At the moment there's no way to remove a deployment, you'll need to manually go to `infra/aws` and run `terraform destroy`. For upgrades you'll also need to run them directly in helm.".yellow()
+            upgrade_command="upgrade --name <deployment_name> --release <release_name>".blue(),
+            destroy_command="destroy --name <deployment_name>".blue(),
         );
 
         Ok(())
     }
+
+    // releases the deployment runs, infra alone runs the ones recorded for the existent deployment
+    fn releases(
+        &self,
+        target: &DeployTarget,
+        name: &str,
+        project: &Project,
+        config: &CoreConfig,
+        registry_url: &str,
+        release_name: &str,
+    ) -> Result<Vec<Release>, Box<dyn std::error::Error>> {
+        let deployment = match target {
+            DeployTarget::Infra => self.infra_deployer.find(name)?,
+            _ => None,
+        };
+        let kinds: &[ArtifactKind] = match target {
+            DeployTarget::Contracts => &[ArtifactKind::Contracts],
+            DeployTarget::Infra => &[
+                ArtifactKind::Node,
+                ArtifactKind::Geth,
+                ArtifactKind::Batcher,
+                ArtifactKind::Proposer,
+                ArtifactKind::Explorer,
+            ],
+            DeployTarget::All => ArtifactKind::all(),
+        };
+
+        let release_manifest_repository = InMemoryReleaseManifestRepository::new(&project.releases);
+        let explorer_enabled = helm::explorer_enabled(&project.infra.helm)?;
+        let mut releases = vec![];
+        for kind in kinds {
+            let artifact = self.artifacts_factory.get(kind, project, config);
+            let (release_name, registry_url) = match &deployment {
+                Some(deployment) => (
+                    deployment.release_for(artifact.name()),
+                    deployment.registry_url.as_str(),
+                ),
+                None => (release_name, registry_url),
+            };
+
+            let release = Release::from_artifact(&artifact, release_name, registry_url)
+                .pinned_by(release_manifest_repository.find(release_name)?.as_ref());
+
+            // releases without an op-explorer image run the upstream blockscout instead
+            if let ArtifactKind::Explorer = kind {
+                if !explorer_enabled || release.digest.is_none() {
+                    continue;
+                }
+            }
+
+            releases.push(release);
+        }
+
+        Ok(releases)
+    }
 }
@@ -0,0 +1,223 @@
+use crate::infra::console::{
+    print_info, print_success, require_inputs, Dialoguer, TDialoguer, BUNDLE_PASSPHRASE_INPUT,
+};
+use clap::Subcommand;
+use colored::*;
+use opraas_core::{
+    domain::{Deployment, ProjectFactory, TDeploymentRepository, TProjectFactory},
+    infra::deployment::{bundle, InMemoryDeploymentRepository},
+};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DeploymentsSubcommand {
+    /// List the deployments of the project
+    List {
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Pack a deployment into a single bundle file, to share it or move it to another machine
+    Export {
+        name: String,
+
+        /// Bundle path, defaults to <name>.opruaas
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+
+        /// Encrypt the bundle with a passphrase
+        #[arg(long, default_value_t = false)]
+        encrypt: bool,
+
+        /// Implies --encrypt
+        #[arg(long, env = "OPRUAAS_BUNDLE_PASSPHRASE", hide_env_values = true)]
+        passphrase: Option<String>,
+    },
+    /// Verify and restore a deployment bundle into ./deployments
+    Import {
+        path: PathBuf,
+
+        #[arg(long, env = "OPRUAAS_BUNDLE_PASSPHRASE", hide_env_values = true)]
+        passphrase: Option<String>,
+
+        /// Replace the deployment if it already exists, archiving the current one
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+}
+
+pub struct DeploymentsCommand {
+    dialoguer: Box<dyn TDialoguer>,
+    deployment_repository: Box<dyn TDeploymentRepository>,
+}
+
+const HEADERS: [&str; 7] = [
+    "NAME",
+    "RELEASE",
+    "REGISTRY",
+    "L1 CHAIN",
+    "L2 CHAIN",
+    "CONTRACTS",
+    "INFRA",
+];
+
+// implementations ================================================
+
+impl DeploymentsCommand {
+    pub fn new() -> Self {
+        let project = ProjectFactory::new().from_cwd().unwrap();
+
+        Self {
+            dialoguer: Box::new(Dialoguer::new()),
+            deployment_repository: Box::new(InMemoryDeploymentRepository::new(&project.root)),
+        }
+    }
+
+    pub fn run(&self, cmd: DeploymentsSubcommand) -> Result<(), Box<dyn std::error::Error>> {
+        match cmd {
+            DeploymentsSubcommand::List { json } => self.list(json),
+            DeploymentsSubcommand::Export {
+                name,
+                output,
+                encrypt,
+                passphrase,
+            } => self.export(name, output, encrypt, passphrase),
+            DeploymentsSubcommand::Import {
+                path,
+                passphrase,
+                force,
+            } => self.import(path, passphrase, force),
+        }
+    }
+
+    fn export(
+        &self,
+        name: String,
+        output: Option<PathBuf>,
+        encrypt: bool,
+        passphrase: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        require_inputs(
+            self.dialoguer.as_ref(),
+            vec![(BUNDLE_PASSPHRASE_INPUT, !encrypt || passphrase.is_some())],
+        )?;
+
+        let passphrase = match (encrypt, passphrase) {
+            (_, Some(passphrase)) => Some(passphrase),
+            (true, None) => Some(self.dialoguer.password("Bundle passphrase", true)),
+            (false, None) => None,
+        };
+        let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.{}", name, bundle::BUNDLE_EXTENSION)));
+
+        self.deployment_repository
+            .export(&name, &output, passphrase.as_deref())?;
+
+        print_success(&format!("✔️ Deployment {} exported to {}", name, output.display()));
+        print_info("Private keys are not included, whoever imports it needs them in their .env");
+
+        Ok(())
+    }
+
+    fn import(
+        &self,
+        path: PathBuf,
+        passphrase: Option<String>,
+        force: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let content = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let encrypted = bundle::is_encrypted(&content);
+        require_inputs(
+            self.dialoguer.as_ref(),
+            vec![(BUNDLE_PASSPHRASE_INPUT, !encrypted || passphrase.is_some())],
+        )?;
+
+        let passphrase = match (encrypted, passphrase) {
+            (true, None) => Some(self.dialoguer.password("Bundle passphrase", false)),
+            (_, passphrase) => passphrase,
+        };
+
+        let deployment = self
+            .deployment_repository
+            .import(&path, passphrase.as_deref(), force)?;
+
+        print_success(&format!(
+            "✔️ Deployment {} imported, release {} from {}",
+            deployment.name, deployment.release_name, deployment.registry_url
+        ));
+
+        Ok(())
+    }
+
+    fn list(&self, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let deployments = self.deployment_repository.list()?;
+
+        if json {
+            let summaries: Vec<Value> = deployments.iter().map(|d| self.summary(d)).collect();
+            println!("{}", serde_json::to_string_pretty(&summaries)?);
+            return Ok(());
+        }
+
+        if deployments.is_empty() {
+            print_info("No deployments found in ./deployments");
+            return Ok(());
+        }
+
+        let rows: Vec<[String; 7]> = deployments.iter().map(|d| self.row(d)).collect();
+        let widths: Vec<usize> = (0..HEADERS.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].len())
+                    .chain(std::iter::once(HEADERS[i].len()))
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        let header: Vec<String> = HEADERS
+            .iter()
+            .zip(&widths)
+            .map(|(h, w)| format!("{:<w$}", h, w = w))
+            .collect();
+        println!("{}", header.join("  ").bold());
+
+        for row in rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{:<w$}", c, w = w))
+                .collect();
+            println!("{}", cells.join("  "));
+        }
+
+        Ok(())
+    }
+
+    fn summary(&self, deployment: &Deployment) -> Value {
+        json!({
+            "name": deployment.name,
+            "release_name": deployment.release_name,
+            "registry_url": deployment.registry_url,
+            "l1_chain_id": deployment.network_config.l1_chain_id,
+            "l2_chain_id": deployment.network_config.l2_chain_id,
+            "contracts_artifacts": deployment.contracts_artifacts.is_some(),
+            "infra_artifacts": deployment.infra_artifacts.is_some(),
+        })
+    }
+
+    fn row(&self, deployment: &Deployment) -> [String; 7] {
+        let present = |artifacts: bool| match artifacts {
+            true => "yes".to_string(),
+            false => "no".to_string(),
+        };
+
+        [
+            deployment.name.clone(),
+            deployment.release_name.clone(),
+            deployment.registry_url.clone(),
+            deployment.network_config.l1_chain_id.to_string(),
+            deployment.network_config.l2_chain_id.to_string(),
+            present(deployment.contracts_artifacts.is_some()),
+            present(deployment.infra_artifacts.is_some()),
+        ]
+    }
+}
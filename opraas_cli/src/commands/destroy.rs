@@ -0,0 +1,119 @@
+use crate::{
+    config::{
+        SystemRequirementsChecker, TSystemRequirementsChecker, HELM_REQUIREMENT, K8S_REQUIREMENT, TERRAFORM_REQUIREMENT,
+    },
+    infra::console::{
+        print_warning, require_inputs, style_spinner, unlock_keystores, Dialoguer, TDialoguer, YES_INPUT,
+    },
+};
+use colored::*;
+use indicatif::{HumanDuration, ProgressBar};
+use opraas_core::{
+    application::stack::{StackInfraDeployerService, TStackInfraDeployerService},
+    domain::{Project, ProjectFactory, Stack, TProjectFactory, TStackInfraDeployer},
+    infra::{
+        deployment::InMemoryDeploymentRepository,
+        stack::{
+            deployer_terraform::TerraformDeployer, repo_inmemory::GitStackInfraRepository, runner_helm::HelmStackRunner,
+        },
+    },
+};
+use std::time::Instant;
+
+pub struct DestroyCommand {
+    dialoguer: Box<dyn TDialoguer>,
+    system_requirement_checker: Box<dyn TSystemRequirementsChecker>,
+    project_factory: Box<dyn TProjectFactory>,
+}
+
+// implementations ================================================
+
+impl DestroyCommand {
+    pub fn new() -> Self {
+        Self {
+            dialoguer: Box::new(Dialoguer::new()),
+            system_requirement_checker: Box::new(SystemRequirementsChecker::new()),
+            project_factory: Box::new(ProjectFactory::new()),
+        }
+    }
+
+    pub fn run(&self, name: String, keep_records: bool, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let project = self.project_factory.from_cwd().unwrap();
+        unlock_keystores(self.dialoguer.as_ref(), &project.root.join("keystore"))?;
+
+        // dev deployments live in the local cluster, everything else went through terraform
+        let is_dev = name == "dev";
+        if is_dev {
+            self.system_requirement_checker
+                .check(vec![K8S_REQUIREMENT, HELM_REQUIREMENT])?;
+        } else {
+            self.system_requirement_checker
+                .check(vec![TERRAFORM_REQUIREMENT])?;
+        }
+
+        require_inputs(self.dialoguer.as_ref(), vec![(YES_INPUT, yes)])?;
+
+        let infra_deployer = self.infra_deployer(&project, is_dev);
+        if infra_deployer.find(&name)?.is_none() {
+            return Err(format!("Deployment {} not found", name).into());
+        }
+
+        print_warning(&format!(
+            "This will tear down every resource of deployment {}, including its kubernetes cluster and volumes.",
+            name
+        ));
+        if !yes
+            && !self
+                .dialoguer
+                .confirm("This can't be undone. Are you sure you want to continue?")
+        {
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        let destroy_spinner = style_spinner(
+            ProgressBar::new_spinner(),
+            &format!("⏳ Destroying {}...", name),
+        );
+
+        infra_deployer.destroy(&Stack::load(&project, &name), keep_records)?;
+
+        destroy_spinner.finish_with_message(format!(
+            "✔️ Destroyed in {}",
+            HumanDuration(started.elapsed())
+        ));
+
+        // print instructions
+
+        let records = match keep_records {
+            true => format!("Deployment records were kept at ./deployments/{}", name),
+            false => "Deployment records were archived at ./deployments/.archive".to_string(),
+        };
+
+        println!(
+            "\n{title}\n\n\
+            {records}\n\n\
+            - {bin} {deploy_cmd}\n\
+            \tCreate a new deployment from your releases.\n",
+            title = "What's Next?".bright_white().bold(),
+            records = records,
+            bin = env!("CARGO_BIN_NAME").blue(),
+            deploy_cmd = "deploy [contracts|infra|all] --name <deployment_name>".blue()
+        );
+
+        Ok(())
+    }
+
+    fn infra_deployer(&self, project: &Project, is_dev: bool) -> Box<dyn TStackInfraDeployerService> {
+        let stack_deployer: Box<dyn TStackInfraDeployer> = match is_dev {
+            true => Box::new(HelmStackRunner::new("opruaas-dev", "opruaas-dev")),
+            false => Box::new(TerraformDeployer::new(&project.root)),
+        };
+
+        Box::new(StackInfraDeployerService::new(
+            stack_deployer,
+            Box::new(GitStackInfraRepository::new()),
+            Box::new(InMemoryDeploymentRepository::new(&project.root)),
+        ))
+    }
+}
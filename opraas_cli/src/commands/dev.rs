@@ -1,39 +1,89 @@
 use crate::config::{
     SystemRequirementsChecker, TSystemRequirementsChecker, DOCKER_REQUIREMENT, HELM_REQUIREMENT, K8S_REQUIREMENT,
 };
-use crate::infra::console::{print_info, print_warning, style_spinner, Dialoguer, TDialoguer};
+use crate::infra::console::{
+    print_info, print_warning, prompt_release_name, require_inputs, style_spinner, Dialoguer, TDialoguer,
+    REGISTRY_INPUT, RELEASE_INPUT, YES_INPUT,
+};
 use assert_cmd::Command;
-use indicatif::ProgressBar;
+use clap::{Subcommand, ValueEnum};
+use colored::*;
+use indicatif::{HumanDuration, ProgressBar};
 use opraas_core::application::{
+    artifact::catalog::{ReleaseCatalogService, TReleaseCatalogService},
+    artifact::release::{ArtifactReleaserService, TArtifactReleaserService},
     contracts::deploy::{StackContractsDeployerService, TStackContractsDeployerService},
     stack::run::{StackRunnerService, TStackRunnerService},
 };
-use opraas_core::config::CoreConfig;
+use opraas_core::config::{ConfigValidator, CoreConfig, TConfigValidator};
 use opraas_core::domain::{
-    ArtifactFactory, ArtifactKind, ProjectFactory, Release, Stack, TArtifactFactory, TProjectFactory,
+    ArtifactFactory, ArtifactKind, DevnetState, Project, ProjectFactory, Release, Stack, TArtifactFactory,
+    TDevnetStateRepository, TProjectFactory, TReleaseManifestRepository,
 };
+use opraas_core::helm;
 use opraas_core::infra::deployment::InMemoryDeploymentRepository;
-use opraas_core::infra::ethereum::{GethTestnetNode, TTestnetNode};
-use opraas_core::infra::release::{DockerReleaseRepository, DockerReleaseRunner};
+use opraas_core::infra::devnet::InMemoryDevnetStateRepository;
+use opraas_core::infra::ethereum::{AnvilOptions, AnvilTestnetNode, EthRpc, GethTestnetNode, JsonRpc, TTestnetNode};
+use opraas_core::infra::release::{
+    DockerLocalRegistry, DockerReleaseRepository, DockerReleaseRunner, InMemoryReleaseManifestRepository,
+    OciReleaseRegistry, TLocalRegistry,
+};
 use opraas_core::infra::stack::repo_inmemory::GitStackInfraRepository;
 use opraas_core::infra::stack::runner_helm::HelmStackRunner;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum DevSubcommand {
+    /// Check the L1 node and pods of a devnet started with --detach
+    Status,
+    /// Tear down a devnet started with --detach, or whatever a killed dev session left behind
+    Stop,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum DevL1 {
+    Geth,
+    Anvil,
+}
+
+/// Anvil settings passed to dev, the ones left out keep the AnvilOptions defaults
+#[derive(Debug, Clone, Default)]
+pub struct AnvilFlags {
+    pub state: Option<PathBuf>,
+    pub block_time: Option<u64>,
+    pub accounts: Option<u32>,
+    pub balance: Option<u64>,
+}
 
 pub struct DevCommand {
     dialoguer: Box<dyn TDialoguer>,
     l1_node: Box<dyn TTestnetNode>,
     stack_runner: Box<dyn TStackRunnerService>,
     system_requirement_checker: Box<dyn TSystemRequirementsChecker>,
+    config_validator: Box<dyn TConfigValidator>,
     artifacts_factory: Box<dyn TArtifactFactory>,
     contracts_deployer: Box<dyn TStackContractsDeployerService>,
+    artifact_releaser: Box<dyn TArtifactReleaserService>,
+    release_catalog: Box<dyn TReleaseCatalogService>,
+    local_registry: Box<dyn TLocalRegistry>,
+    devnet_state_repository: Box<dyn TDevnetStateRepository>,
+    eth_rpc: Box<dyn EthRpc>,
     project_factory: Box<dyn TProjectFactory>,
+    /// set while the devnet belongs to this process, so dropping the command tears it down
+    teardown_on_drop: AtomicBool,
 }
 
 const DEFAULT_REGISTRY: &str = "wakeuplabs";
 const DEFAULT_RELEASE_TAG: &str = "v0.0.4";
+const DEV_RELEASE_NAME: &str = "opruaas-dev";
+const DEV_NAMESPACE: &str = "opruaas-dev";
+const L1_PORT: u64 = 8545;
+const LOCAL_REGISTRY_PORT: u64 = 5000;
+const LOCAL_RELEASE_TAG: &str = "local";
 
 // implementations ================================================
 
@@ -46,23 +96,118 @@ impl DevCommand {
             dialoguer: Box::new(Dialoguer::new()),
             l1_node: Box::new(GethTestnetNode::new()),
             stack_runner: Box::new(StackRunnerService::new(
-                Box::new(HelmStackRunner::new("opruaas-dev", "opruaas-dev")),
+                Box::new(HelmStackRunner::new(DEV_RELEASE_NAME, DEV_NAMESPACE)),
                 Box::new(GitStackInfraRepository::new()),
             )),
             system_requirement_checker: Box::new(SystemRequirementsChecker::new()),
+            config_validator: Box::new(ConfigValidator::new()),
             artifacts_factory: Box::new(ArtifactFactory::new()),
             contracts_deployer: Box::new(StackContractsDeployerService::new(
                 Box::new(InMemoryDeploymentRepository::new(&project.root)),
-                Box::new(DockerReleaseRepository::new()),
+                Box::new(DockerReleaseRepository::new(&project.releases)),
                 Box::new(DockerReleaseRunner::new()),
             )),
+            artifact_releaser: Box::new(ArtifactReleaserService::new(Box::new(
+                DockerReleaseRepository::new(&project.releases),
+            ))),
+            release_catalog: Box::new(ReleaseCatalogService::new(Box::new(
+                OciReleaseRegistry::new(),
+            ))),
+            local_registry: Box::new(DockerLocalRegistry::new(LOCAL_REGISTRY_PORT)),
+            devnet_state_repository: Box::new(InMemoryDevnetStateRepository::new(&project.root)),
+            eth_rpc: Box::new(JsonRpc::new()),
             project_factory,
+            teardown_on_drop: AtomicBool::new(false),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &mut self,
+        cmd: Option<DevSubcommand>,
+        default: bool,
+        registry_url: Option<String>,
+        release_name: Option<String>,
+        local_images: bool,
+        l1: DevL1,
+        anvil_flags: AnvilFlags,
+        detach: bool,
+        yes: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match cmd {
+            Some(DevSubcommand::Status) => self.status(),
+            Some(DevSubcommand::Stop) => self.stop(),
+            None => self.start(
+                default,
+                registry_url,
+                release_name,
+                local_images,
+                l1,
+                anvil_flags,
+                detach,
+                yes,
+            ),
         }
     }
 
-    pub fn run(&self, default: bool) -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::too_many_arguments)]
+    fn start(
+        &mut self,
+        default: bool,
+        registry_url: Option<String>,
+        release_name: Option<String>,
+        local_images: bool,
+        l1: DevL1,
+        anvil_flags: AnvilFlags,
+        detach: bool,
+        yes: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         self.system_requirement_checker
             .check(vec![DOCKER_REQUIREMENT, K8S_REQUIREMENT, HELM_REQUIREMENT])?;
+        if self.devnet_state_repository.find()?.is_some() {
+            return Err(
+                "A detached devnet is already running, check it with dev status or tear it down with dev stop".into(),
+            );
+        }
+        self.l1_node = match l1 {
+            DevL1::Geth
+                if anvil_flags.state.is_some()
+                    || anvil_flags.block_time.is_some()
+                    || anvil_flags.accounts.is_some()
+                    || anvil_flags.balance.is_some() =>
+            {
+                return Err(
+                    "--l1-state, --block-time, --accounts and --balance are only supported with --l1 anvil".into(),
+                )
+            }
+            DevL1::Geth => Box::new(GethTestnetNode::new()),
+            // the devnet deploys and funds from the first account
+            DevL1::Anvil if anvil_flags.accounts == Some(0) => return Err("--accounts must be at least 1".into()),
+            DevL1::Anvil => {
+                let defaults = AnvilOptions::default();
+                Box::new(AnvilTestnetNode::new(AnvilOptions {
+                    block_time: anvil_flags.block_time.unwrap_or(defaults.block_time),
+                    accounts: anvil_flags.accounts.unwrap_or(defaults.accounts),
+                    balance: anvil_flags.balance.unwrap_or(defaults.balance),
+                    state: anvil_flags.state,
+                    ..defaults
+                }))
+            }
+        };
+        require_inputs(
+            self.dialoguer.as_ref(),
+            vec![
+                (
+                    REGISTRY_INPUT,
+                    default || local_images || registry_url.is_some(),
+                ),
+                (
+                    RELEASE_INPUT,
+                    default || local_images || release_name.is_some(),
+                ),
+                (YES_INPUT, yes),
+            ],
+        )?;
 
         let project = self.project_factory.from_cwd().unwrap();
         let mut config = CoreConfig::new_from_toml(&project.config)?;
@@ -78,10 +223,12 @@ impl DevCommand {
             .output()?;
         let current_context = String::from_utf8_lossy(&current_context_cmd.stdout);
 
-        if !self.dialoguer.confirm(&format!(
-            "Confirm that your kubernetes context is pointing to local: {}",
-            current_context
-        )) {
+        if !yes
+            && !self.dialoguer.confirm(&format!(
+                "Confirm that your kubernetes context is pointing to local: {}",
+                current_context
+            ))
+        {
             print_warning("Aborting...");
             print_info("We need you to switch your kubernetes context to local");
             print_info("You can change your kubernetes context with kubectl config use-context");
@@ -90,23 +237,34 @@ impl DevCommand {
 
         // request release name and repository to test
 
-        let registry_url: String = match default {
-            true => DEFAULT_REGISTRY.to_string(),
-            false => self
+        // local images are released to the local registry once it's up
+        let registry_url: String = match (registry_url, default || local_images) {
+            (Some(registry_url), _) => registry_url,
+            (None, true) => DEFAULT_REGISTRY.to_string(),
+            (None, false) => self
                 .dialoguer
                 .prompt("Input Docker registry url (e.g. dockerhub.io/wakeuplabs) "),
         };
 
-        let release_name: String = match default {
-            true => DEFAULT_RELEASE_TAG.to_string(),
-            false => self.dialoguer.prompt("Input release name (e.g. v0.1.0)"),
+        let release_name: String = match (release_name, default || local_images) {
+            _ if local_images => LOCAL_RELEASE_TAG.to_string(),
+            (Some(release_name), _) => release_name,
+            (None, true) => DEFAULT_RELEASE_TAG.to_string(),
+            (None, false) => prompt_release_name(
+                self.dialoguer.as_ref(),
+                self.release_catalog.as_ref(),
+                &registry_url,
+            ),
         };
 
         // update config for devnet mode
 
         let wallet_address = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
         let wallet_private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
-        config.network.l1_chain_id = 1337;
+        // geth dev mode only runs 1337, anvil keeps the configured l1 chain id
+        if let DevL1::Geth = l1 {
+            config.network.l1_chain_id = 1337;
+        }
         config.accounts.admin_address = wallet_address.to_string();
         config.accounts.admin_private_key = wallet_private_key.to_string();
         config.accounts.batcher_address = wallet_address.to_string();
@@ -121,15 +279,26 @@ impl DevCommand {
         config.accounts.challenger_private_key = wallet_private_key.to_string();
         config.network.l1_rpc_url = "http://host.docker.internal:8545".to_string();
         config.network.fund_dev_accounts = true;
+        config.accounts.resolve()?;
+        self.config_validator.validate(&config)?;
 
         // start local network ===========================
 
         let l1_spinner = style_spinner(ProgressBar::new_spinner(), "⏳ Starting l1 node...");
 
-        self.l1_node.start(config.network.l1_chain_id, 8545)?;
+        // from here on anything left running is ours to clean up
+        self.teardown_on_drop.store(true, Ordering::SeqCst);
+        self.l1_node.start(config.network.l1_chain_id, L1_PORT)?;
 
         l1_spinner.finish_with_message("✔️ L1 node ready...");
 
+        // release local images ===========================
+
+        let registry_url = match local_images {
+            true => self.release_local_images(&project, &config)?,
+            false => registry_url,
+        };
+
         // Deploy contracts ===========================
 
         let contracts_spinner = style_spinner(
@@ -143,6 +312,11 @@ impl DevCommand {
                 .get(&ArtifactKind::Contracts, &project, &config),
             &release_name,
             &registry_url,
+        )
+        .pinned_by(
+            InMemoryReleaseManifestRepository::new(&project.releases)
+                .find(&release_name)?
+                .as_ref(),
         );
 
         let contracts_deployment = self
@@ -161,6 +335,7 @@ impl DevCommand {
         self.stack_runner.start(&Stack::new(
             project.infra.helm.clone(),
             project.infra.aws.clone(),
+            project.releases.clone(),
             Some(contracts_deployment),
         ))?;
 
@@ -178,6 +353,37 @@ impl DevCommand {
 
         print_info("\n\n================================================\n\n");
 
+        if detach {
+            self.devnet_state_repository.save(&DevnetState {
+                l1_node: format!("{:?}", l1).to_lowercase(),
+                l1_container: self.l1_node.container_name().to_string(),
+                l1_port: L1_PORT,
+                helm_release: HelmStackRunner::new(DEV_RELEASE_NAME, DEV_NAMESPACE).helm_release_name(),
+                namespace: DEV_NAMESPACE.to_string(),
+                deployment_name: "dev".to_string(),
+                started_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            })?;
+            self.teardown_on_drop.store(false, Ordering::SeqCst);
+
+            println!(
+                "{title}\n\n\
+                The devnet keeps running in the background, its state is recorded at ./.opruaas/devnet.json\n\n\
+                - {bin} {status_cmd}\n\
+                \tCheck the L1 node and pods.\n\
+                - {bin} {logs_cmd}\n\
+                \tStream the logs of every component.\n\
+                - {bin} {stop_cmd}\n\
+                \tTear it down.\n",
+                title = "What's Next?".bright_white().bold(),
+                bin = env!("CARGO_BIN_NAME").blue(),
+                status_cmd = "dev status".blue(),
+                logs_cmd = "logs --all --follow".blue(),
+                stop_cmd = "dev stop".blue(),
+            );
+
+            return Ok(());
+        }
+
         print_warning("Press Ctrl + C to exit...");
 
         let running = Arc::new(AtomicBool::new(true));
@@ -195,22 +401,170 @@ impl DevCommand {
 
         Ok(())
     }
+
+    fn status(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let state = match self.devnet_state_repository.find()? {
+            Some(state) => state,
+            None => {
+                print_info("No detached devnet running, start one with dev --detach");
+                return Ok(());
+            }
+        };
+
+        let started_at = UNIX_EPOCH + Duration::from_secs(state.started_at);
+        println!(
+            "\n{} {}\n",
+            format!("Devnet {}", state.deployment_name).bold(),
+            format!(
+                "started {} ago",
+                HumanDuration(started_at.elapsed().unwrap_or_default())
+            )
+            .dimmed()
+        );
+
+        let l1_rpc = format!("http://localhost:{}", state.l1_port);
+        let l1_healthy = match self
+            .eth_rpc
+            .send_rpc_request(&l1_rpc, 1, "eth_blockNumber", vec![])
+        {
+            Ok(response) => {
+                let block = response["result"].as_str().unwrap_or_default();
+                let block = u64::from_str_radix(block.trim_start_matches("0x"), 16).unwrap_or_default();
+                println!(
+                    "{}  L1 node {} at {}, block {}",
+                    "✔ up   ".green(),
+                    state.l1_container,
+                    l1_rpc,
+                    block
+                );
+                true
+            }
+            Err(_) => {
+                println!(
+                    "{}  L1 node {} is not responding at {}",
+                    "✘ down ".red().bold(),
+                    state.l1_container,
+                    l1_rpc
+                );
+                false
+            }
+        };
+
+        let pods = helm::list_pods(&state.namespace, None)?;
+        if pods.is_empty() {
+            println!(
+                "{}  No pods in namespace {}, helm release {} may have been removed",
+                "✘ down ".red().bold(),
+                state.namespace,
+                state.helm_release
+            );
+        }
+
+        let width = pods.iter().map(|pod| pod.name.len()).max().unwrap_or(0);
+        for pod in &pods {
+            let status = match pod.ready {
+                true => "✔ ready".green(),
+                false => "! wait ".yellow(),
+            };
+            println!(
+                "{}  {:<width$}  {} {}",
+                status,
+                pod.name,
+                pod.status,
+                format!("{} restarts", pod.restarts).dimmed(),
+                width = width
+            );
+        }
+        println!();
+
+        if !l1_healthy || pods.is_empty() || pods.iter().any(|pod| !pod.ready) {
+            return Err("Devnet is not healthy, check the failing components with logs".into());
+        }
+
+        Ok(())
+    }
+
+    fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let l1_nodes: Vec<Box<dyn TTestnetNode>> = match self.devnet_state_repository.find()? {
+            Some(state) if state.l1_node == "anvil" => vec![Box::new(AnvilTestnetNode::new(AnvilOptions {
+                container_name: state.l1_container,
+                ..Default::default()
+            }))],
+            Some(_) => vec![Box::new(GethTestnetNode::new())],
+            None => {
+                print_info("No detached devnet recorded, cleaning up anything a previous dev session left behind");
+                vec![
+                    Box::new(GethTestnetNode::new()),
+                    Box::new(AnvilTestnetNode::new(AnvilOptions::default())),
+                ]
+            }
+        };
+
+        let stop_spinner = style_spinner(ProgressBar::new_spinner(), "⏳ Stopping devnet...");
+
+        self.teardown(&l1_nodes)?;
+        self.devnet_state_repository.delete()?;
+
+        stop_spinner.finish_with_message("✔️ Devnet stopped");
+
+        Ok(())
+    }
+
+    /// Pushes the images built with the build command to the local registry, returns the registry url
+    fn release_local_images(
+        &self,
+        project: &Project,
+        config: &CoreConfig,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let registry_spinner = style_spinner(
+            ProgressBar::new_spinner(),
+            "⏳ Pushing local images to local registry...",
+        );
+
+        let registry_url = self.local_registry.start()?;
+        for artifact in self.artifacts_factory.get_all(project, config) {
+            self.artifact_releaser
+                .release(&artifact, LOCAL_RELEASE_TAG, &registry_url, &[])
+                .map_err(|e| format!("{}, build it first with the build command", e))?;
+        }
+
+        registry_spinner.finish_with_message(format!("✔️ Local images available at {}...", registry_url));
+
+        Ok(registry_url)
+    }
+
+    /// Stops the l1 nodes and uninstalls the chart, trying everything even if something fails
+    fn teardown(&self, l1_nodes: &[Box<dyn TTestnetNode>]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut errors = vec![];
+
+        for l1_node in l1_nodes {
+            if let Err(e) = l1_node.stop() {
+                errors.push(format!("Failed to stop l1 node: {}", e));
+            }
+        }
+        if let Err(e) = self.stack_runner.stop() {
+            errors.push(format!("Failed to stop stack runner: {}", e));
+        }
+        if let Err(e) = self.local_registry.stop() {
+            errors.push(format!("Failed to stop local registry: {}", e));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("\n").into());
+        }
+
+        Ok(())
+    }
 }
 
 impl Drop for DevCommand {
     fn drop(&mut self) {
-        match self.l1_node.stop() {
-            Ok(_) => {}
-            Err(e) => {
-                print_warning(&format!("Failed to stop l1 node: {}", e));
-            }
+        if !self.teardown_on_drop.load(Ordering::SeqCst) {
+            return;
         }
 
-        match self.stack_runner.stop() {
-            Ok(_) => {}
-            Err(e) => {
-                print_warning(&format!("Failed to stop stack runner: {}", e));
-            }
+        if let Err(e) = self.teardown(std::slice::from_ref(&self.l1_node)) {
+            print_warning(&e.to_string());
         }
     }
 }
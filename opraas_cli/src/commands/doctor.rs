@@ -0,0 +1,65 @@
+use crate::config::{CheckResult, CheckStatus, SystemDoctor, TSystemDoctor};
+use colored::*;
+
+pub struct DoctorCommand {
+    doctor: Box<dyn TSystemDoctor>,
+}
+
+// implementations ================================================
+
+impl DoctorCommand {
+    pub fn new() -> Self {
+        Self {
+            doctor: Box::new(SystemDoctor::new()),
+        }
+    }
+
+    pub fn run(&self, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let results = self.doctor.diagnose();
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&results)?);
+        } else {
+            self.print(&results);
+        }
+
+        let failed = results
+            .iter()
+            .filter(|result| result.status == CheckStatus::Fail)
+            .count();
+        if failed > 0 {
+            return Err(format!("{} checks failed", failed).into());
+        }
+
+        Ok(())
+    }
+
+    fn print(&self, results: &[CheckResult]) {
+        let width = results
+            .iter()
+            .map(|result| result.name.len())
+            .max()
+            .unwrap_or(0);
+
+        println!();
+        for result in results {
+            let status = match result.status {
+                CheckStatus::Pass => "✔ pass".green(),
+                CheckStatus::Warn => "! warn".yellow(),
+                CheckStatus::Fail => "✘ fail".red().bold(),
+            };
+            println!(
+                "{}  {:<width$}  {}",
+                status,
+                result.name,
+                result.message,
+                width = width
+            );
+
+            if let Some(hint) = &result.hint {
+                println!("        {}", format!("↳ {}", hint).dimmed());
+            }
+        }
+        println!();
+    }
+}
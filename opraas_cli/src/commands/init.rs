@@ -1,11 +1,13 @@
 use crate::config::{SystemRequirementsChecker, TSystemRequirementsChecker, GIT_REQUIREMENT};
-use crate::infra::console::{print_error, style_spinner};
+use crate::infra::console::{print_error, style_transfer_bar};
 use clap::ValueEnum;
 use colored::*;
-use indicatif::{HumanDuration, ProgressBar};
-use opraas_core::application::initialize::{ArtifactInitializer, TArtifactInitializerService};
+use indicatif::{HumanBytes, HumanDuration, MultiProgress, ProgressBar};
+use opraas_core::application::initialize::{ArtifactInitializer, SourceUpdate, TArtifactInitializerService};
 use opraas_core::config::CoreConfig;
-use opraas_core::domain::{ArtifactFactory, ArtifactKind, ProjectFactory, TArtifactFactory, TProjectFactory};
+use opraas_core::domain::{
+    ArtifactFactory, ArtifactKind, ProjectFactory, PullProgress, TArtifactFactory, TProjectFactory,
+};
 use opraas_core::infra::artifact::GitArtifactSourceRepository;
 use std::{sync::Arc, thread, time::Instant};
 
@@ -16,6 +18,7 @@ pub enum InitTargets {
     Contracts,
     Proposer,
     Geth,
+    Explorer,
     All,
 }
 
@@ -40,12 +43,12 @@ impl InitCommand {
         }
     }
 
-    pub fn run(&self, target: InitTargets) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn run(&self, target: InitTargets, update: bool, force: bool) -> Result<(), Box<dyn std::error::Error>> {
         self.system_requirement_checker
             .check(vec![GIT_REQUIREMENT])?;
 
         let project = self.project_factory.from_cwd().unwrap();
-        let config = CoreConfig::new_from_toml(&project.config).unwrap();
+        let config = CoreConfig::new_from_toml(&project.config)?;
 
         // assemble list of artifacts to build
         let artifacts = match target {
@@ -65,21 +68,14 @@ impl InitCommand {
             InitTargets::Geth => vec![self
                 .artifacts_factory
                 .get(&ArtifactKind::Geth, &project, &config)],
+            InitTargets::Explorer => vec![self
+                .artifacts_factory
+                .get(&ArtifactKind::Explorer, &project, &config)],
         };
 
-        // start timer and spinner
+        // start timer and one progress bar per artifact
         let started = Instant::now();
-        let init_spinner = style_spinner(
-            ProgressBar::new_spinner(),
-            &format!(
-                "⏳ Initializing {}...",
-                artifacts
-                    .iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ),
-        );
+        let progress_bars = MultiProgress::new();
 
         // iterate over the artifacts and download
         let handles: Vec<_> = artifacts
@@ -87,11 +83,32 @@ impl InitCommand {
             .map(|&ref artifact| {
                 let artifact = Arc::new(artifact.clone());
                 let artifact_initializer = Arc::clone(&self.artifact_initializer);
+                let progress_bar = progress_bars.add(style_transfer_bar(
+                    ProgressBar::new(0),
+                    &artifact.to_string(),
+                ));
 
                 thread::spawn(move || {
-                    match artifact_initializer.initialize(&artifact) {
-                        Ok(_) => {}
+                    let transfer_bar = progress_bar.clone();
+                    let on_progress = move |progress: PullProgress| {
+                        transfer_bar.set_length(progress.total_objects as u64);
+                        transfer_bar.set_position(progress.received_objects as u64);
+                        transfer_bar.set_message(HumanBytes(progress.received_bytes as u64).to_string());
+                    };
+
+                    let initialized = match update {
+                        true => artifact_initializer
+                            .update(&artifact, force, &on_progress)
+                            .map(|source_update| describe_update(&source_update)),
+                        false => artifact_initializer
+                            .initialize(&artifact, &on_progress)
+                            .map(|_| "ready".to_string()),
+                    };
+
+                    match initialized {
+                        Ok(message) => progress_bar.finish_with_message(format!("✔️ {}", message)),
                         Err(e) => {
+                            progress_bar.abandon_with_message("❌ failed");
                             print_error(&format!("❌ Error initializing {}", artifact));
                             return Err(e.to_string());
                         }
@@ -115,7 +132,7 @@ impl InitCommand {
             }
         }
 
-        init_spinner.finish_with_message(format!("Done in {}", HumanDuration(started.elapsed())));
+        println!("Done in {}", HumanDuration(started.elapsed()));
 
         // print instructions ========================================
 
@@ -140,3 +157,16 @@ impl InitCommand {
         Ok(())
     }
 }
+
+// old and new commit of an updated source, abbreviated like git does
+fn describe_update(source_update: &SourceUpdate) -> String {
+    let short = |commit: &str| commit.chars().take(7).collect::<String>();
+
+    match &source_update.previous_commit {
+        None => format!("pulled at {}", short(&source_update.commit)),
+        Some(previous) if *previous == source_update.commit => {
+            format!("already at {}", short(&source_update.commit))
+        }
+        Some(previous) => format!("{} → {}", short(previous), short(&source_update.commit)),
+    }
+}
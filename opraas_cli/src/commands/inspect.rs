@@ -43,7 +43,7 @@ impl InspectCommand {
         Self {
             contracts_deployer: Box::new(StackContractsDeployerService::new(
                 Box::new(InMemoryDeploymentRepository::new(&project.root)),
-                Box::new(DockerReleaseRepository::new()),
+                Box::new(DockerReleaseRepository::new(&project.releases)),
                 Box::new(DockerReleaseRunner::new()),
             )),
             contracts_inspector: Box::new(StackContractsInspectorService::new()),
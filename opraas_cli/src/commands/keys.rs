@@ -0,0 +1,158 @@
+use crate::infra::console::{
+    print_info, print_success, print_warning, require_inputs, Dialoguer, TDialoguer, KEYSTORE_PASSWORD_INPUT, YES_INPUT,
+};
+use clap::Subcommand;
+use colored::*;
+use opraas_core::{
+    application::accounts::{AccountsGeneratorService, GeneratedAccount, TAccountsGeneratorService},
+    domain::{ProjectFactory, TProjectFactory},
+    infra::{
+        ethereum::{Wallet, DEFAULT_DERIVATION_PATH},
+        project::InMemoryProjectRepository,
+    },
+};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum KeysSubcommand {
+    /// Generate the admin, batcher, sequencer, proposer, deployer and challenger accounts
+    Generate {
+        /// Derive the accounts from a bip39 mnemonic, a new one is generated when no phrase is given
+        #[arg(long, env = "OPRUAAS_MNEMONIC", hide_env_values = true, num_args = 0..=1, default_missing_value = "")]
+        mnemonic: Option<String>,
+
+        /// Base derivation path, each account appends its index
+        #[arg(long, default_value = DEFAULT_DERIVATION_PATH, requires = "mnemonic")]
+        derivation_path: String,
+
+        /// Write the private keys to web3 secret storage keystores in ./keystore instead of .env
+        #[arg(long, default_value_t = false)]
+        encrypt: bool,
+
+        #[arg(
+            long,
+            env = "OPRUAAS_KEYSTORE_PASSWORD",
+            hide_env_values = true,
+            requires = "encrypt"
+        )]
+        keystore_password: Option<String>,
+
+        #[arg(long, env = "OPRUAAS_YES", default_value_t = false)]
+        yes: bool,
+    },
+}
+
+pub struct KeysCommand {
+    dialoguer: Box<dyn TDialoguer>,
+    accounts_generator: Box<dyn TAccountsGeneratorService>,
+    project_factory: Box<dyn TProjectFactory>,
+}
+
+// implementations ================================================
+
+impl KeysCommand {
+    pub fn new() -> Self {
+        Self {
+            dialoguer: Box::new(Dialoguer::new()),
+            accounts_generator: Box::new(AccountsGeneratorService::new(Box::new(
+                InMemoryProjectRepository::new(),
+            ))),
+            project_factory: Box::new(ProjectFactory::new()),
+        }
+    }
+
+    pub fn run(&self, cmd: KeysSubcommand) -> Result<(), Box<dyn std::error::Error>> {
+        match cmd {
+            KeysSubcommand::Generate {
+                mnemonic,
+                derivation_path,
+                encrypt,
+                keystore_password,
+                yes,
+            } => self.generate(mnemonic, derivation_path, encrypt, keystore_password, yes),
+        }
+    }
+
+    fn generate(
+        &self,
+        mnemonic: Option<String>,
+        derivation_path: String,
+        encrypt: bool,
+        keystore_password: Option<String>,
+        yes: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let project = self
+            .project_factory
+            .from_cwd()
+            .ok_or("Not inside a project")?;
+
+        require_inputs(
+            self.dialoguer.as_ref(),
+            vec![
+                (
+                    KEYSTORE_PASSWORD_INPUT,
+                    !encrypt || keystore_password.is_some(),
+                ),
+                (YES_INPUT, yes),
+            ],
+        )?;
+
+        if !yes
+            && !self
+                .dialoguer
+                .confirm("This will replace the accounts in .env and config.toml. Continue?")
+        {
+            return Ok(());
+        }
+
+        let keystore_password = match (encrypt, keystore_password) {
+            (false, _) => None,
+            (true, Some(password)) => Some(password),
+            (true, None) => Some(self.dialoguer.password("Keystore password", true)),
+        };
+
+        // empty mnemonic means the flag was given without a phrase
+        let mnemonic = match mnemonic {
+            Some(mnemonic) if mnemonic.is_empty() => {
+                let mnemonic = Wallet::generate_mnemonic();
+                print_warning("Generated a new mnemonic, store it safely. It won't be shown again and it's the only way to recover the accounts:");
+                println!("\n  {}\n", mnemonic.bright_white().bold());
+                Some(mnemonic)
+            }
+            mnemonic => mnemonic,
+        };
+
+        let accounts = self
+            .accounts_generator
+            .generate(mnemonic.as_deref(), &derivation_path)?;
+        self.accounts_generator
+            .save(&project, &accounts, keystore_password.as_deref())?;
+
+        self.print(&accounts);
+        match encrypt {
+            true => {
+                print_success("✔️ Addresses written to .env and config.toml, keystores to ./keystore");
+                print_info("Private keys are not in .env anymore, commands that need them will ask for the keystore password (or read OPRUAAS_KEYSTORE_PASSWORD)");
+            }
+            false => print_success("✔️ Accounts written to .env and config.toml"),
+        }
+
+        Ok(())
+    }
+
+    fn print(&self, accounts: &[GeneratedAccount]) {
+        println!();
+        for account in accounts {
+            println!(
+                "{} {} {}",
+                format!("{:<12}", account.role).bold(),
+                account.wallet.address,
+                account
+                    .derivation_path
+                    .as_deref()
+                    .unwrap_or_default()
+                    .dimmed()
+            );
+        }
+        println!();
+    }
+}
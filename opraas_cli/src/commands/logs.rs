@@ -0,0 +1,104 @@
+use crate::config::{SystemRequirementsChecker, TSystemRequirementsChecker, K8S_REQUIREMENT};
+use clap::ValueEnum;
+use colored::*;
+use opraas_core::{
+    domain::{LogsOptions, ProjectFactory, Stack, StackComponent, TProjectFactory, TStackLogs},
+    infra::stack::{deployer_terraform::TerraformDeployer, runner_helm::HelmStackRunner},
+};
+use std::{collections::HashMap, sync::Mutex};
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum LogsTarget {
+    Node,
+    Geth,
+    Batcher,
+    Proposer,
+    Explorer,
+}
+
+pub struct LogsCommand {
+    system_requirement_checker: Box<dyn TSystemRequirementsChecker>,
+    project_factory: Box<dyn TProjectFactory>,
+}
+
+// pods get a color in order of appearance, cycling when there are more pods than colors
+const POD_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::Green,
+    Color::Blue,
+    Color::BrightRed,
+];
+
+// implementations ================================================
+
+impl From<&LogsTarget> for StackComponent {
+    fn from(target: &LogsTarget) -> Self {
+        match target {
+            LogsTarget::Node => StackComponent::Node,
+            LogsTarget::Geth => StackComponent::Geth,
+            LogsTarget::Batcher => StackComponent::Batcher,
+            LogsTarget::Proposer => StackComponent::Proposer,
+            LogsTarget::Explorer => StackComponent::Explorer,
+        }
+    }
+}
+
+impl LogsCommand {
+    pub fn new() -> Self {
+        Self {
+            system_requirement_checker: Box::new(SystemRequirementsChecker::new()),
+            project_factory: Box::new(ProjectFactory::new()),
+        }
+    }
+
+    pub fn run(
+        &self,
+        target: Option<LogsTarget>,
+        name: String,
+        follow: bool,
+        since: Option<String>,
+        all: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.system_requirement_checker
+            .check(vec![K8S_REQUIREMENT])?;
+
+        let project = self.project_factory.from_cwd().unwrap();
+        let stack = Stack::load(&project, &name);
+
+        let is_dev = name == "dev";
+        if !is_dev && stack.deployment.is_none() {
+            return Err(format!("Deployment {} not found", name).into());
+        }
+
+        let components: Vec<StackComponent> = match (target, all) {
+            (_, true) => StackComponent::all().to_vec(),
+            (Some(target), false) => vec![StackComponent::from(&target)],
+            (None, false) => return Err("Specify a component or use --all".into()),
+        };
+
+        // same release and namespace the dev and deploy commands install the chart with
+        let stack_logs: Box<dyn TStackLogs> = match is_dev {
+            true => Box::new(HelmStackRunner::new("opruaas-dev", "opruaas-dev")),
+            false => Box::new(TerraformDeployer::new(&project.root)),
+        };
+
+        let pod_colors: Mutex<HashMap<String, Color>> = Mutex::new(HashMap::new());
+        stack_logs.logs(
+            &stack,
+            &components,
+            &LogsOptions { follow, since },
+            &|pod, line| {
+                // holding the lock while printing keeps lines from different pods whole
+                let mut pod_colors = pod_colors.lock().unwrap();
+                let next_color = POD_COLORS[pod_colors.len() % POD_COLORS.len()];
+                let color = *pod_colors.entry(pod.to_string()).or_insert(next_color);
+
+                println!("{} {}", format!("{} |", pod).color(color), line);
+            },
+        )?;
+
+        Ok(())
+    }
+}
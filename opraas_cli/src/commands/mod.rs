@@ -1,15 +1,33 @@
 pub mod build;
+pub mod config;
 pub mod deploy;
+pub mod deployments;
+pub mod destroy;
 pub mod dev;
+pub mod doctor;
 pub mod init;
 pub mod inspect;
+pub mod keys;
+pub mod logs;
+pub mod monitor;
 pub mod new;
 pub mod release;
+pub mod status;
+pub mod upgrade;
 
 pub use build::BuildCommand;
+pub use config::ConfigCommand;
 pub use deploy::DeployCommand;
+pub use deployments::DeploymentsCommand;
+pub use destroy::DestroyCommand;
 pub use dev::DevCommand;
+pub use doctor::DoctorCommand;
 pub use init::InitCommand;
 pub use inspect::InspectCommand;
+pub use keys::KeysCommand;
+pub use logs::LogsCommand;
+pub use monitor::MonitorCommand;
 pub use new::NewCommand;
 pub use release::ReleaseCommand;
+pub use status::StatusCommand;
+pub use upgrade::UpgradeCommand;
@@ -0,0 +1,137 @@
+use crate::infra::console::{print_info, print_warning};
+use clap::ValueEnum;
+use colored::*;
+use indicatif::HumanDuration;
+use opraas_core::{
+    application::{
+        contracts::{StackContractsInspectorService, TStackContractsInspectorService},
+        monitor::{OnchainMonitorService, OnchainStatus, TOnchainMonitorService},
+    },
+    domain::{ProjectFactory, TDeploymentRepository, TProjectFactory},
+    infra::{deployment::InMemoryDeploymentRepository, ethereum::JsonRpc},
+};
+use std::{
+    io::Cursor,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum MonitorTarget {
+    Onchain,
+}
+
+pub struct MonitorCommand {
+    deployment_repository: Box<dyn TDeploymentRepository>,
+    contracts_inspector: Box<dyn TStackContractsInspectorService>,
+}
+
+// implementations ================================================
+
+impl MonitorCommand {
+    pub fn new() -> Self {
+        let project = ProjectFactory::new().from_cwd().unwrap();
+
+        Self {
+            deployment_repository: Box::new(InMemoryDeploymentRepository::new(&project.root)),
+            contracts_inspector: Box::new(StackContractsInspectorService::new()),
+        }
+    }
+
+    pub fn run(&self, target: MonitorTarget, name: String, interval: u64) -> Result<(), Box<dyn std::error::Error>> {
+        match target {
+            MonitorTarget::Onchain => self.onchain(&name, interval),
+        }
+    }
+
+    fn onchain(&self, name: &str, interval: u64) -> Result<(), Box<dyn std::error::Error>> {
+        let deployment = match self.deployment_repository.find(name)? {
+            Some(deployment) => deployment,
+            None => return Err(format!("Deployment {} not found", name).into()),
+        };
+        let contracts_artifacts = match &deployment.contracts_artifacts {
+            Some(contracts_artifacts) => contracts_artifacts,
+            None => return Err(format!("Deployment {} has no contracts artifacts", name).into()),
+        };
+
+        let artifacts = self
+            .contracts_inspector
+            .inspect(Cursor::new(std::fs::read(contracts_artifacts)?))?;
+        let mut monitor = OnchainMonitorService::new(
+            Box::new(JsonRpc::new()),
+            &deployment,
+            &artifacts["addresses"],
+        )?;
+
+        print_info(&format!(
+            "Monitoring batches and proposals of {} on {}",
+            name, deployment.network_config.l1_rpc_url
+        ));
+        print_warning("Press Ctrl + C to exit...");
+
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = Arc::clone(&running);
+        ctrlc::set_handler(move || running_clone.store(false, Ordering::SeqCst))?;
+
+        while running.load(Ordering::SeqCst) {
+            match monitor.poll() {
+                Ok(status) => self.report(&status),
+                Err(e) => print_warning(&format!("Failed to poll L1: {}", e)),
+            }
+
+            for _ in 0..interval {
+                if !running.load(Ordering::SeqCst) {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(1));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn report(&self, status: &OnchainStatus) {
+        let describe = |since: Option<u64>, threshold: u64, delayed: bool| {
+            let elapsed = match since {
+                Some(since) => format!("{} ago", HumanDuration(Duration::from_secs(since))),
+                None => "none found".to_string(),
+            };
+            let message = format!(
+                "{} (expected within {})",
+                elapsed,
+                HumanDuration(Duration::from_secs(threshold))
+            );
+
+            match delayed {
+                true => message.yellow().bold(),
+                false => message.green(),
+            }
+        };
+
+        println!(
+            "[L1 #{}] last batch: {} | last proposal: {}",
+            status.l1_block,
+            describe(
+                status.since_last_batch,
+                status.batch_threshold,
+                status.batches_delayed()
+            ),
+            describe(
+                status.since_last_proposal,
+                status.proposal_threshold,
+                status.proposals_delayed()
+            ),
+        );
+
+        if status.batches_delayed() {
+            print_warning("⚠️ Batches are late, check op-batcher logs and its L1 balance");
+        }
+        if status.proposals_delayed() {
+            print_warning("⚠️ Proposals are late, check op-proposer logs and its L1 balance");
+        }
+    }
+}
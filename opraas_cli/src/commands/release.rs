@@ -1,17 +1,27 @@
 use crate::{
     config::{SystemRequirementsChecker, TSystemRequirementsChecker, DOCKER_REQUIREMENT, GIT_REQUIREMENT},
-    infra::console::{print_error, print_info, print_warning, style_spinner, Dialoguer, TDialoguer},
+    infra::console::{
+        print_error, print_info, print_warning, require_inputs, style_spinner, Dialoguer, TDialoguer, REGISTRY_INPUT,
+        RELEASE_INPUT,
+    },
 };
-use clap::ValueEnum;
+use clap::{Subcommand, ValueEnum};
 use colored::*;
 use indicatif::{HumanDuration, ProgressBar};
 use opraas_core::{
-    application::{ArtifactReleaserService, TArtifactReleaserService},
+    application::{
+        catalog::{ReleaseCatalogService, TReleaseCatalogService},
+        ArtifactReleaserService, TArtifactReleaserService,
+    },
     config::CoreConfig,
     domain::{
-        ArtifactFactory, ArtifactKind, ProjectFactory, TArtifactFactory, TProjectFactory, TProjectVersionControl,
+        ArtifactFactory, ArtifactKind, ProjectFactory, Release, TArtifactFactory, TProjectFactory,
+        TProjectVersionControl, TReleaseManifestRepository,
+    },
+    infra::{
+        project::GitVersionControl,
+        release::{DockerReleaseRepository, InMemoryReleaseManifestRepository, OciReleaseRegistry},
     },
-    infra::{project::GitVersionControl, release::DockerReleaseRepository},
 };
 use std::{sync::Arc, thread, time::Instant};
 
@@ -21,6 +31,7 @@ pub struct ReleaseCommand {
     system_requirements_checker: Box<dyn TSystemRequirementsChecker>,
     artifacts_factory: Box<dyn TArtifactFactory>,
     artifacts_releaser: Arc<dyn TArtifactReleaserService>,
+    release_catalog: Box<dyn TReleaseCatalogService>,
     project_factory: Box<dyn TProjectFactory>,
 }
 
@@ -31,45 +42,122 @@ pub enum ReleaseTargets {
     Contracts,
     Proposer,
     Geth,
+    Explorer,
     All,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum ReleaseSubcommand {
+    /// Copy an existing release to another registry or name without rebuilding it
+    Promote {
+        /// Release to copy, as <registry>/<release> (e.g. localhost:5000/v0.1.0)
+        #[arg(long)]
+        from: String,
+
+        /// Where to copy it, as <registry>/<release> (e.g. docker.io/wakeuplabs/v0.1.0)
+        #[arg(long)]
+        to: String,
+    },
+
+    /// List the releases pushed to a registry and whether they have every artifact
+    List {
+        /// Registry to query (e.g. docker.io/wakeuplabs)
+        #[arg(long, env = "OPRUAAS_REGISTRY")]
+        registry: String,
+    },
+}
+
 // implementations ================================================
 
 impl ReleaseCommand {
     pub fn new() -> Self {
+        let project_factory = Box::new(ProjectFactory::new());
+        // release list works outside a project, it doesn't record anything
+        let releases_dir = project_factory
+            .from_cwd()
+            .map(|project| project.releases)
+            .unwrap_or_default();
+
         Self {
             version_control: Box::new(GitVersionControl::new()),
             dialoguer: Box::new(Dialoguer::new()),
             system_requirements_checker: Box::new(SystemRequirementsChecker::new()),
             artifacts_factory: Box::new(ArtifactFactory::new()),
             artifacts_releaser: Arc::new(ArtifactReleaserService::new(Box::new(
-                DockerReleaseRepository::new(),
+                DockerReleaseRepository::new(&releases_dir),
             ))),
-            project_factory: Box::new(ProjectFactory::new()),
+            release_catalog: Box::new(ReleaseCatalogService::new(Box::new(
+                OciReleaseRegistry::new(),
+            ))),
+            project_factory,
         }
     }
 
-    pub fn run(&self, target: ReleaseTargets) -> Result<(), Box<dyn std::error::Error>> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn run(
+        &self,
+        cmd: Option<ReleaseSubcommand>,
+        target: Option<ReleaseTargets>,
+        registry_url: Option<String>,
+        release_name: Option<String>,
+        platforms: Vec<String>,
+        git_tag: bool,
+        yes: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match (cmd, target) {
+            (Some(ReleaseSubcommand::Promote { from, to }), _) => self.promote(&from, &to),
+            (Some(ReleaseSubcommand::List { registry }), _) => self.list(&registry),
+            (None, Some(target)) => self.release(target, registry_url, release_name, platforms, git_tag, yes),
+            (None, None) => Err("Specify a target to release".into()),
+        }
+    }
+
+    fn release(
+        &self,
+        target: ReleaseTargets,
+        registry_url: Option<String>,
+        release_name: Option<String>,
+        platforms: Vec<String>,
+        git_tag: bool,
+        yes: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         self.system_requirements_checker
             .check(vec![GIT_REQUIREMENT, DOCKER_REQUIREMENT])?;
+        require_inputs(
+            self.dialoguer.as_ref(),
+            vec![
+                (REGISTRY_INPUT, registry_url.is_some()),
+                (RELEASE_INPUT, release_name.is_some()),
+            ],
+        )?;
 
         let project = self.project_factory.from_cwd().unwrap();
-        let config = CoreConfig::new_from_toml(&project.config).unwrap();
+        let config = CoreConfig::new_from_toml(&project.config)?;
 
         // request release name and repository
         print_info("We'll tag your local builds and push them to your registry.");
         print_warning("Make sure your docker user has push permissions to the registry");
+        if !platforms.is_empty() {
+            print_info(&format!(
+                "Images will be built for {} with docker buildx and pushed as multi-platform manifest lists",
+                platforms.join(", ")
+            ));
+        }
 
-        let registry_url: String = self
-            .dialoguer
-            .prompt("Input Docker registry url (e.g. dockerhub.io/wakeuplabs) ");
-        let release_name: String = self.dialoguer.prompt("Input release name (e.g. v0.1.0)");
+        let registry_url: String = registry_url.unwrap_or_else(|| {
+            self.dialoguer
+                .prompt("Input Docker registry url (e.g. dockerhub.io/wakeuplabs) ")
+        });
+        let release_name: String =
+            release_name.unwrap_or_else(|| self.dialoguer.prompt("Input release name (e.g. v0.1.0)"));
 
-        // Offer option to tag release in git
-        if self
-            .dialoguer
-            .confirm("Would you also like to tag your local git repository?")
+        // Offer option to tag release in git, unattended runs only tag when asked to
+        let unattended = yes || !self.dialoguer.is_interactive();
+        if git_tag
+            || (!unattended
+                && self
+                    .dialoguer
+                    .confirm("Would you also like to tag your local git repository?"))
         {
             self.version_control
                 .tag(&project.root.to_str().unwrap(), &release_name)?;
@@ -95,6 +183,9 @@ impl ReleaseCommand {
             ReleaseTargets::Geth => vec![self
                 .artifacts_factory
                 .get(&ArtifactKind::Geth, &project, &config)],
+            ReleaseTargets::Explorer => vec![self
+                .artifacts_factory
+                .get(&ArtifactKind::Explorer, &project, &config)],
         };
 
         let started = Instant::now();
@@ -115,11 +206,12 @@ impl ReleaseCommand {
             .map(|&ref artifact| {
                 let release_name = release_name.clone();
                 let registry_url = registry_url.clone();
+                let platforms = platforms.clone();
                 let artifact = Arc::clone(artifact);
                 let artifacts_releaser = Arc::clone(&self.artifacts_releaser);
 
                 thread::spawn(move || -> Result<(), String> {
-                    match artifacts_releaser.release(&artifact, &release_name, &registry_url) {
+                    match artifacts_releaser.release(&artifact, &release_name, &registry_url, &platforms) {
                         Ok(_) => {}
                         Err(e) => {
                             print_error(&format!("❌ Error releasing {}", artifact));
@@ -166,4 +258,149 @@ impl ReleaseCommand {
 
         Ok(())
     }
+
+    fn promote(&self, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.system_requirements_checker
+            .check(vec![DOCKER_REQUIREMENT])?;
+
+        let (from_registry, from_name) = parse_release_ref(from)?;
+        let (to_registry, to_name) = parse_release_ref(to)?;
+
+        let project = self.project_factory.from_cwd().unwrap();
+        let config = CoreConfig::new_from_toml(&project.config)?;
+
+        print_warning("Make sure your docker user can pull from the source registry and push to the target one");
+
+        // the manifest tells which artifacts the release has and their digests, without one every artifact is expected
+        let manifest = InMemoryReleaseManifestRepository::new(&project.releases)
+            .find(from_name)?
+            .filter(|manifest| {
+                manifest
+                    .artifacts
+                    .iter()
+                    .any(|released| released.registry_url == from_registry)
+            });
+        let releases: Vec<_> = self
+            .artifacts_factory
+            .get_all(&project, &config)
+            .into_iter()
+            .filter(|artifact| {
+                manifest
+                    .as_ref()
+                    .is_none_or(|manifest| manifest.find(artifact.name(), from_registry).is_some())
+            })
+            .map(|artifact| {
+                let release = Release::from_artifact(&artifact, from_name, from_registry).pinned_by(manifest.as_ref());
+                (artifact, release)
+            })
+            .collect();
+
+        let started = Instant::now();
+        let promote_spinner = style_spinner(
+            ProgressBar::new_spinner(),
+            &format!("⏳ Promoting {} to {}...", from, to),
+        );
+
+        let handles: Vec<_> = releases
+            .into_iter()
+            .map(|(artifact, from_release)| {
+                let to_registry = to_registry.to_string();
+                let to_name = to_name.to_string();
+                let artifacts_releaser = Arc::clone(&self.artifacts_releaser);
+
+                thread::spawn(move || -> Result<(), String> {
+                    match artifacts_releaser.promote(&artifact, &from_release, &to_registry, &to_name) {
+                        Ok(_) => {}
+                        Err(e) => {
+                            print_error(&format!("❌ Error promoting {}", artifact));
+                            return Err(e.to_string());
+                        }
+                    }
+                    Ok(())
+                })
+            })
+            .collect();
+
+        // wait for all threads to complete
+        for handle in handles {
+            match handle.join() {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => return Err(Box::new(std::io::Error::other(e))),
+                Err(_) => return Err(Box::new(std::io::Error::other("Thread panicked"))),
+            }
+        }
+
+        promote_spinner.finish_with_message(format!(
+            "✔️ Promoted {} to {} in {}, digests match",
+            from,
+            to,
+            HumanDuration(started.elapsed())
+        ));
+
+        Ok(())
+    }
+
+    fn list(&self, registry_url: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let list_spinner = style_spinner(
+            ProgressBar::new_spinner(),
+            &format!("⏳ Querying {} for releases...", registry_url),
+        );
+        let releases = self.release_catalog.list(registry_url);
+        list_spinner.finish_and_clear();
+        let releases = releases?;
+
+        if releases.is_empty() {
+            print_warning(&format!("No releases found in {}", registry_url));
+            return Ok(());
+        }
+
+        println!(
+            "\n{}\n",
+            format!("Releases in {}", registry_url)
+                .bright_white()
+                .bold()
+        );
+        for release in releases {
+            match release.is_complete() {
+                true => println!("- {} {}", release.name.blue(), "complete".green()),
+                false => println!(
+                    "- {} {}",
+                    release.name.blue(),
+                    format!("missing {}", release.missing().join(", ")).yellow()
+                ),
+            }
+        }
+        println!();
+
+        Ok(())
+    }
+}
+
+// <registry>/<release>, the registry itself can have slashes
+fn parse_release_ref(release_ref: &str) -> Result<(&str, &str), Box<dyn std::error::Error>> {
+    match release_ref.rsplit_once('/') {
+        Some((registry_url, release_name)) if !registry_url.is_empty() && !release_name.is_empty() => {
+            Ok((registry_url, release_name))
+        }
+        _ => Err(format!("Expected <registry>/<release>, got {}", release_ref).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_split_release_refs_on_the_last_slash() {
+        assert_eq!(
+            parse_release_ref("localhost:5000/v0.1.0").unwrap(),
+            ("localhost:5000", "v0.1.0")
+        );
+        assert_eq!(
+            parse_release_ref("docker.io/wakeuplabs/v0.1.0").unwrap(),
+            ("docker.io/wakeuplabs", "v0.1.0")
+        );
+        assert!(parse_release_ref("v0.1.0").is_err());
+        assert!(parse_release_ref("wakeuplabs/").is_err());
+    }
 }
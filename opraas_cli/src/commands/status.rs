@@ -0,0 +1,125 @@
+use colored::*;
+use opraas_core::{
+    application::monitor::{ChainEndpoints, ChainStatus, ChainStatusService, TChainStatusService},
+    domain::{Deployment, Project, ProjectFactory, TDeploymentRepository, TProjectFactory},
+    infra::{deployment::InMemoryDeploymentRepository, ethereum::JsonRpc},
+};
+
+pub struct StatusCommand {
+    deployment_repository: Box<dyn TDeploymentRepository>,
+    chain_status: Box<dyn TChainStatusService>,
+    project_factory: Box<dyn TProjectFactory>,
+}
+
+// as exposed by the dev command
+const DEV_L1_RPC: &str = "http://localhost:8545";
+const DEV_L2_RPC: &str = "http://localhost:80/rpc";
+
+// implementations ================================================
+
+impl StatusCommand {
+    pub fn new() -> Self {
+        let project_factory = Box::new(ProjectFactory::new());
+        let project = project_factory.from_cwd().unwrap();
+
+        Self {
+            deployment_repository: Box::new(InMemoryDeploymentRepository::new(&project.root)),
+            chain_status: Box::new(ChainStatusService::new(Box::new(JsonRpc::new()))),
+            project_factory,
+        }
+    }
+
+    pub fn run(
+        &self,
+        name: String,
+        l1_rpc: Option<String>,
+        l2_rpc: Option<String>,
+        node_rpc: Option<String>,
+        json: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let project = self.project_factory.from_cwd().unwrap();
+        let deployment = match self.deployment_repository.find(&name)? {
+            Some(deployment) => deployment,
+            None => return Err(format!("Deployment {} not found", name).into()),
+        };
+
+        let endpoints = ChainEndpoints {
+            l1_rpc: match l1_rpc {
+                Some(l1_rpc) => l1_rpc,
+                None => self.default_l1_rpc(&deployment),
+            },
+            l2_rpc: match l2_rpc {
+                Some(l2_rpc) => l2_rpc,
+                None => self.default_l2_rpc(&project, &deployment)?,
+            },
+            // op-node rpc is not exposed by the chart ingress
+            node_rpc: node_rpc.ok_or(
+                "op-node rpc is only reachable from inside the cluster, expose it and pass --node-rpc. \
+                For example: kubectl port-forward service/op-node-sequencer-service 7545:7545 --namespace <namespace>",
+            )?,
+        };
+
+        let status = self.chain_status.status(&endpoints)?;
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&status)?);
+        } else {
+            self.print(&name, &status);
+        }
+
+        Ok(())
+    }
+
+    fn default_l1_rpc(&self, deployment: &Deployment) -> String {
+        match deployment.name.as_str() {
+            "dev" => DEV_L1_RPC.to_string(),
+            _ => deployment.network_config.l1_rpc_url.clone(),
+        }
+    }
+
+    fn default_l2_rpc(&self, project: &Project, deployment: &Deployment) -> Result<String, Box<dyn std::error::Error>> {
+        if deployment.name == "dev" {
+            return Ok(DEV_L2_RPC.to_string());
+        }
+
+        match &deployment.infra_artifacts {
+            Some(infra_artifacts) => {
+                ChainEndpoints::l2_rpc_from_infra(infra_artifacts, &project.infra.helm.join("values.yaml"))
+            }
+            None => Err(format!(
+                "Deployment {} has no infra artifacts, pass --l2-rpc",
+                deployment.name
+            )
+            .into()),
+        }
+    }
+
+    fn print(&self, name: &str, status: &ChainStatus) {
+        let sequencer = match status.sequencer_active {
+            true => "active".green(),
+            false => "stopped".red().bold(),
+        };
+        let lag = format!("{} L1 blocks behind", status.l1_origin_lag);
+
+        println!(
+            "\n{title}\n\n\
+            L2 chain id       {chain_id}\n\
+            Sequencer         {sequencer}\n\
+            L1 head           #{l1_head}\n\
+            L2 unsafe head    #{unsafe_head} (L1 origin #{l1_origin}, {lag})\n\
+            L2 safe head      #{safe_head}\n\
+            L2 finalized head #{finalized_head}\n\
+            op-geth head      #{l2_head}\n",
+            title = format!("Status of {}", name).bright_white().bold(),
+            chain_id = status.l2_chain_id,
+            sequencer = sequencer,
+            l1_head = status.l1_head,
+            unsafe_head = status.unsafe_l2.number,
+            l1_origin = status.unsafe_l2.l1_origin,
+            lag = lag,
+            safe_head = status.safe_l2.number,
+            finalized_head = status.finalized_l2.number,
+            l2_head = status.l2_head,
+        );
+    }
+}
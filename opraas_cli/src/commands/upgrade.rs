@@ -0,0 +1,152 @@
+use crate::{
+    config::{
+        SystemRequirementsChecker, TSystemRequirementsChecker, AWS_REQUIREMENT, HELM_REQUIREMENT, K8S_REQUIREMENT,
+        TERRAFORM_REQUIREMENT,
+    },
+    infra::console::{print_info, require_inputs, style_spinner, unlock_keystores, Dialoguer, TDialoguer, YES_INPUT},
+};
+use clap::ValueEnum;
+use colored::*;
+use indicatif::{HumanDuration, ProgressBar};
+use opraas_core::{
+    application::stack::{StackInfraDeployerService, TStackInfraDeployerService},
+    domain::{ArtifactKind, Project, ProjectFactory, Stack, TProjectFactory, TStackInfraDeployer},
+    infra::{
+        deployment::InMemoryDeploymentRepository,
+        stack::{
+            deployer_terraform::TerraformDeployer, repo_inmemory::GitStackInfraRepository, runner_helm::HelmStackRunner,
+        },
+    },
+};
+use std::time::Instant;
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum UpgradeTarget {
+    Node,
+    Geth,
+    Batcher,
+    Proposer,
+    Explorer,
+}
+
+pub struct UpgradeCommand {
+    dialoguer: Box<dyn TDialoguer>,
+    system_requirement_checker: Box<dyn TSystemRequirementsChecker>,
+    project_factory: Box<dyn TProjectFactory>,
+}
+
+// implementations ================================================
+
+impl From<&UpgradeTarget> for ArtifactKind {
+    fn from(target: &UpgradeTarget) -> Self {
+        match target {
+            UpgradeTarget::Node => ArtifactKind::Node,
+            UpgradeTarget::Geth => ArtifactKind::Geth,
+            UpgradeTarget::Batcher => ArtifactKind::Batcher,
+            UpgradeTarget::Proposer => ArtifactKind::Proposer,
+            UpgradeTarget::Explorer => ArtifactKind::Explorer,
+        }
+    }
+}
+
+impl UpgradeCommand {
+    pub fn new() -> Self {
+        Self {
+            dialoguer: Box::new(Dialoguer::new()),
+            system_requirement_checker: Box::new(SystemRequirementsChecker::new()),
+            project_factory: Box::new(ProjectFactory::new()),
+        }
+    }
+
+    pub fn run(
+        &self,
+        name: String,
+        release: String,
+        only: Vec<UpgradeTarget>,
+        yes: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // dev runs on the local cluster, everything else is upgraded through terraform and reached with the aws cli
+        let is_dev = name == "dev";
+        if is_dev {
+            self.system_requirement_checker
+                .check(vec![K8S_REQUIREMENT, HELM_REQUIREMENT])?;
+        } else {
+            self.system_requirement_checker.check(vec![
+                K8S_REQUIREMENT,
+                TERRAFORM_REQUIREMENT,
+                AWS_REQUIREMENT,
+            ])?;
+        }
+
+        require_inputs(self.dialoguer.as_ref(), vec![(YES_INPUT, yes)])?;
+
+        let project = self.project_factory.from_cwd().unwrap();
+        unlock_keystores(self.dialoguer.as_ref(), &project.root.join("keystore"))?;
+
+        let infra_deployer = self.infra_deployer(&project, is_dev);
+        let deployment = match infra_deployer.find(&name)? {
+            Some(deployment) => deployment,
+            None => return Err(format!("Deployment {} not found", name).into()),
+        };
+
+        let artifacts: Vec<ArtifactKind> = only.iter().map(ArtifactKind::from).collect();
+        let components = match artifacts.is_empty() {
+            true => "all components".to_string(),
+            false => artifacts
+                .iter()
+                .map(|artifact| artifact.artifact_name())
+                .collect::<Vec<&str>>()
+                .join(", "),
+        };
+
+        print_info(&format!(
+            "Upgrading {} of deployment {} from {} to {}",
+            components, name, deployment.release_name, release
+        ));
+        if !yes && !self.dialoguer.confirm("Do you want to continue?") {
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        let upgrade_spinner = style_spinner(
+            ProgressBar::new_spinner(),
+            &format!("⏳ Upgrading {}...", name),
+        );
+
+        infra_deployer.upgrade(&Stack::load(&project, &name), &release, &artifacts)?;
+
+        upgrade_spinner.finish_with_message(format!(
+            "✔️ Upgraded and rolled out in {}",
+            HumanDuration(started.elapsed())
+        ));
+
+        // print instructions
+
+        println!(
+            "\n{title}\n\n\
+            Release {release} was recorded at ./deployments/{name}/config/release.json\n\n\
+            - {bin} {inspect_cmd}\n\
+            \tDisplay the artifacts for the deployment.\n",
+            title = "What's Next?".bright_white().bold(),
+            release = release,
+            name = name,
+            bin = env!("CARGO_BIN_NAME").blue(),
+            inspect_cmd = format!("inspect infra --deployment {}", name).blue()
+        );
+
+        Ok(())
+    }
+
+    fn infra_deployer(&self, project: &Project, is_dev: bool) -> Box<dyn TStackInfraDeployerService> {
+        let stack_deployer: Box<dyn TStackInfraDeployer> = match is_dev {
+            true => Box::new(HelmStackRunner::new("opruaas-dev", "opruaas-dev")),
+            false => Box::new(TerraformDeployer::new(&project.root)),
+        };
+
+        Box::new(StackInfraDeployerService::new(
+            stack_deployer,
+            Box::new(GitStackInfraRepository::new()),
+            Box::new(InMemoryDeploymentRepository::new(&project.root)),
+        ))
+    }
+}
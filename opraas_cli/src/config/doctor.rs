@@ -0,0 +1,457 @@
+use super::{
+    Requirement, SystemRequirementsChecker, TSystemRequirementsChecker, DOCKER_REQUIREMENT, GIT_REQUIREMENT,
+    HELM_REQUIREMENT, K8S_REQUIREMENT, TERRAFORM_REQUIREMENT,
+};
+use crate::infra::system::{System, TSystem};
+use opraas_core::{
+    config::{AccountsConfig, NetworkConfig},
+    domain::{ProjectFactory, TProjectFactory},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+pub trait TSystemDoctor {
+    fn diagnose(&self) -> Vec<CheckResult>;
+}
+
+pub struct SystemDoctor {
+    system: Box<dyn TSystem>,
+    requirements_checker: Box<dyn TSystemRequirementsChecker>,
+}
+
+// recommended specs, as stated in the README
+const RECOMMENDED_RAM_GB: u64 = 16;
+const RECOMMENDED_DISK_GB: u64 = 25;
+
+// repositories of the chart dependencies
+const CHART_REPOSITORIES: [(&str, &str); 3] = [
+    ("blockscout", "https://blockscout.github.io/helm-charts"),
+    ("grafana", "https://grafana.github.io/helm-charts"),
+    (
+        "prometheus-community",
+        "https://prometheus-community.github.io/helm-charts",
+    ),
+];
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+// implementations =============================================
+
+impl CheckResult {
+    fn pass(name: &str, message: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Pass,
+            message: message.to_string(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &str, message: &str, hint: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Warn,
+            message: message.to_string(),
+            hint: Some(hint.to_string()),
+        }
+    }
+
+    fn fail(name: &str, message: &str, hint: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Fail,
+            message: message.to_string(),
+            hint: Some(hint.to_string()),
+        }
+    }
+}
+
+impl SystemDoctor {
+    pub fn new() -> Self {
+        Self {
+            system: Box::new(System::new()),
+            requirements_checker: Box::new(SystemRequirementsChecker::new()),
+        }
+    }
+
+    fn check_requirement(&self, requirement: Requirement) -> CheckResult {
+        let name = requirement.program.to_string();
+        let hint = format!(
+            "Install {} {} {}",
+            requirement.program, requirement.required_comparator, requirement.required_version
+        );
+
+        match self.requirements_checker.check(vec![requirement]) {
+            Ok(_) => CheckResult::pass(&name, "installed"),
+            Err(e) => CheckResult::fail(&name, &e, &hint),
+        }
+    }
+
+    fn check_docker_daemon(&self) -> CheckResult {
+        match self
+            .system
+            .execute_command(Command::new("docker").args(["info", "--format", "{{.ServerVersion}}"]))
+        {
+            Ok(version) => CheckResult::pass(
+                "docker daemon",
+                &format!("reachable, server {}", version.trim()),
+            ),
+            Err(_) => CheckResult::fail(
+                "docker daemon",
+                "not reachable",
+                "Start Docker Desktop or the docker service and make sure your user can access it",
+            ),
+        }
+    }
+
+    fn check_kube_context(&self) -> CheckResult {
+        let context = match self
+            .system
+            .execute_command(Command::new("kubectl").args(["config", "current-context"]))
+        {
+            Ok(context) => context.trim().to_string(),
+            Err(_) => {
+                return CheckResult::fail(
+                    "kube context",
+                    "no current context",
+                    "Select a context with kubectl config use-context <context>",
+                )
+            }
+        };
+
+        match self
+            .system
+            .execute_command(Command::new("kubectl").args(["cluster-info", "--request-timeout=5s"]))
+        {
+            Ok(_) => CheckResult::pass("kube context", &format!("{} is reachable", context)),
+            Err(_) => CheckResult::fail(
+                "kube context",
+                &format!("{} is not reachable", context),
+                "Start your local kubernetes (e.g. enable it in Docker Desktop) or switch context with kubectl config use-context",
+            ),
+        }
+    }
+
+    fn check_helm_repos(&self) -> CheckResult {
+        let repos: Vec<Value> = self
+            .system
+            .execute_command(Command::new("helm").args(["repo", "list", "--output", "json"]))
+            .ok()
+            .and_then(|output| serde_json::from_str(&output).ok())
+            .unwrap_or_default();
+
+        let missing: Vec<&(&str, &str)> = CHART_REPOSITORIES
+            .iter()
+            .filter(|(_, url)| {
+                !repos
+                    .iter()
+                    .any(|repo| repo["url"].as_str().map(|u| u.trim_end_matches('/')) == Some(*url))
+            })
+            .collect();
+
+        if missing.is_empty() {
+            return CheckResult::pass("helm repos", "chart dependency repositories configured");
+        }
+
+        CheckResult::warn(
+            "helm repos",
+            &format!(
+                "missing {}",
+                missing
+                    .iter()
+                    .map(|(_, url)| *url)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            &missing
+                .iter()
+                .map(|(name, url)| format!("helm repo add {} {}", name, url))
+                .collect::<Vec<_>>()
+                .join(" && "),
+        )
+    }
+
+    fn check_aws_credentials(&self) -> CheckResult {
+        match self
+            .system
+            .execute_command(Command::new("aws").args(["sts", "get-caller-identity", "--output", "json"]))
+        {
+            Ok(identity) => {
+                let identity: Value = serde_json::from_str(&identity).unwrap_or_default();
+                CheckResult::pass(
+                    "aws credentials",
+                    &format!(
+                        "resolved as {}",
+                        identity["Arn"].as_str().unwrap_or("unknown")
+                    ),
+                )
+            }
+            Err(_) => CheckResult::warn(
+                "aws credentials",
+                "could not be resolved, terraform deployments will fail",
+                "Run aws configure, or export AWS_PROFILE or AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY",
+            ),
+        }
+    }
+
+    fn check_memory(&self) -> CheckResult {
+        let total = match self.total_memory() {
+            Some(total) => total,
+            None => {
+                return CheckResult::warn(
+                    "memory",
+                    "could not determine total memory",
+                    &format!(
+                        "Make sure you have at least {} GB of RAM",
+                        RECOMMENDED_RAM_GB
+                    ),
+                )
+            }
+        };
+
+        match total >= RECOMMENDED_RAM_GB * GB {
+            true => CheckResult::pass("memory", &format!("{} GB", total / GB)),
+            false => CheckResult::warn(
+                "memory",
+                &format!("{} GB, {} GB recommended", total / GB, RECOMMENDED_RAM_GB),
+                "Close other programs or raise the memory available to Docker",
+            ),
+        }
+    }
+
+    fn check_disk(&self) -> CheckResult {
+        let available = match self.available_disk() {
+            Some(available) => available,
+            None => {
+                return CheckResult::warn(
+                    "disk",
+                    "could not determine available disk",
+                    &format!(
+                        "Make sure you have at least {} GB free",
+                        RECOMMENDED_DISK_GB
+                    ),
+                )
+            }
+        };
+
+        match available >= RECOMMENDED_DISK_GB * GB {
+            true => CheckResult::pass("disk", &format!("{} GB available", available / GB)),
+            false => CheckResult::warn(
+                "disk",
+                &format!(
+                    "{} GB available, {} GB recommended",
+                    available / GB,
+                    RECOMMENDED_DISK_GB
+                ),
+                "Free some space, docker system prune removes unused images and build cache",
+            ),
+        }
+    }
+
+    fn check_env(&self, lookup: &dyn Fn(&str) -> bool, keystore_dir: &Path) -> CheckResult {
+        // keys encrypted with `keys generate --encrypt` are unlocked from their keystore when needed
+        let locked = AccountsConfig::locked_roles(keystore_dir);
+        let locked_vars: Vec<String> = locked
+            .iter()
+            .map(|role| format!("{}_PRIVATE_KEY", role.to_uppercase()))
+            .collect();
+
+        let missing: Vec<&str> = AccountsConfig::ENV_VARS
+            .iter()
+            .chain(NetworkConfig::ENV_VARS.iter())
+            .filter(|var| !lookup(var) && !locked_vars.iter().any(|locked_var| locked_var == *var))
+            .copied()
+            .collect();
+
+        match (missing.is_empty(), locked.is_empty()) {
+            (true, true) => CheckResult::pass("env", "all variables set"),
+            (true, false) => CheckResult::pass(
+                "env",
+                &format!("all variables set, {} in keystore", locked.join(", ")),
+            ),
+            (false, _) => CheckResult::fail(
+                "env",
+                &format!("missing {}", missing.join(", ")),
+                "Add them to your .env, use .env.sample as reference",
+            ),
+        }
+    }
+
+    fn keystore_dir(&self) -> PathBuf {
+        match ProjectFactory::new().from_cwd() {
+            Some(project) => project.root.join("keystore"),
+            None => PathBuf::from("keystore"),
+        }
+    }
+
+    fn total_memory(&self) -> Option<u64> {
+        match cfg!(target_os = "macos") {
+            true => self
+                .system
+                .execute_command(Command::new("sysctl").args(["-n", "hw.memsize"]))
+                .ok()?
+                .trim()
+                .parse()
+                .ok(),
+            false => {
+                // MemTotal:       16318480 kB
+                let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+                let line = meminfo.lines().find(|line| line.starts_with("MemTotal:"))?;
+                let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+                Some(kb * 1024)
+            }
+        }
+    }
+
+    fn available_disk(&self) -> Option<u64> {
+        // Filesystem 1024-blocks Used Available Capacity Mounted on
+        let output = self
+            .system
+            .execute_command(Command::new("df").args(["-Pk", "."]))
+            .ok()?;
+        let kb: u64 = output
+            .lines()
+            .nth(1)?
+            .split_whitespace()
+            .nth(3)?
+            .parse()
+            .ok()?;
+
+        Some(kb * 1024)
+    }
+}
+
+impl TSystemDoctor for SystemDoctor {
+    fn diagnose(&self) -> Vec<CheckResult> {
+        let mut results: Vec<CheckResult> = vec![
+            DOCKER_REQUIREMENT,
+            K8S_REQUIREMENT,
+            HELM_REQUIREMENT,
+            TERRAFORM_REQUIREMENT,
+            GIT_REQUIREMENT,
+        ]
+        .into_iter()
+        .map(|requirement| self.check_requirement(requirement))
+        .collect();
+
+        results.push(self.check_docker_daemon());
+        results.push(self.check_kube_context());
+        results.push(self.check_helm_repos());
+        results.push(self.check_aws_credentials());
+        results.push(self.check_memory());
+        results.push(self.check_disk());
+        results.push(self.check_env(&|var| std::env::var(var).is_ok(), &self.keystore_dir()));
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::system::MockTSystem;
+
+    fn doctor(system: MockTSystem) -> SystemDoctor {
+        SystemDoctor {
+            system: Box::new(system),
+            requirements_checker: Box::new(SystemRequirementsChecker::new()),
+        }
+    }
+
+    #[test]
+    fn test_kube_context_unreachable() {
+        let mut mock_system = MockTSystem::new();
+        mock_system.expect_execute_command().returning(|command| {
+            match command.get_args().next().and_then(|a| a.to_str()) {
+                Some("config") => Ok("docker-desktop\n".to_string()),
+                _ => Err("Unable to connect to the server".to_string()),
+            }
+        });
+
+        let result = doctor(mock_system).check_kube_context();
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(result.message, "docker-desktop is not reachable");
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    fn test_helm_repos_missing() {
+        let mut mock_system = MockTSystem::new();
+        mock_system.expect_execute_command().returning(|_| {
+            Ok(r#"[{"name":"grafana","url":"https://grafana.github.io/helm-charts"},{"name":"prometheus-community","url":"https://prometheus-community.github.io/helm-charts/"}]"#.to_string())
+        });
+
+        let result = doctor(mock_system).check_helm_repos();
+
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert_eq!(
+            result.message,
+            "missing https://blockscout.github.io/helm-charts"
+        );
+    }
+
+    #[test]
+    fn test_disk_below_recommendation() {
+        let mut mock_system = MockTSystem::new();
+        mock_system.expect_execute_command().returning(|_| {
+            Ok("Filesystem 1024-blocks Used Available Capacity Mounted on\n/dev/disk1 500000000 490000000 10485760 98% /\n".to_string())
+        });
+
+        let result = doctor(mock_system).check_disk();
+
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert_eq!(result.message, "10 GB available, 25 GB recommended");
+    }
+
+    #[test]
+    fn test_env_reports_missing_variables() {
+        let keystore_dir = tempfile::tempdir().unwrap();
+
+        let result = doctor(MockTSystem::new()).check_env(
+            &|var| var != "BATCHER_PRIVATE_KEY" && var != "L1_RPC_URL",
+            keystore_dir.path(),
+        );
+
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert_eq!(result.message, "missing BATCHER_PRIVATE_KEY, L1_RPC_URL");
+    }
+
+    #[test]
+    fn test_env_accepts_keys_in_keystore() {
+        let keystore_dir = tempfile::tempdir().unwrap();
+        std::fs::write(keystore_dir.path().join("admin.json"), "{}").unwrap();
+        std::fs::write(keystore_dir.path().join("batcher.json"), "{}").unwrap();
+
+        let result = doctor(MockTSystem::new()).check_env(
+            &|var| var != "ADMIN_PRIVATE_KEY" && var != "BATCHER_PRIVATE_KEY",
+            keystore_dir.path(),
+        );
+
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(
+            result.message,
+            "all variables set, admin, batcher in keystore"
+        );
+    }
+}
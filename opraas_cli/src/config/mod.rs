@@ -1,3 +1,5 @@
+pub mod doctor;
 pub mod requirements;
 
+pub use doctor::*;
 pub use requirements::*;
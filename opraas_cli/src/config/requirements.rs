@@ -55,6 +55,12 @@ pub const TERRAFORM_REQUIREMENT: Requirement = Requirement {
     required_version: "1.9.8",
     required_comparator: Comparison::GreaterThanOrEqual,
 };
+pub const AWS_REQUIREMENT: Requirement = Requirement {
+    program: "aws",
+    version_arg: "--version",
+    required_version: "2.0.0",
+    required_comparator: Comparison::GreaterThanOrEqual,
+};
 pub const GIT_REQUIREMENT: Requirement = Requirement {
     program: "git",
     version_arg: "--version",
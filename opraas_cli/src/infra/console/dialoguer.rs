@@ -1,4 +1,5 @@
 use dialoguer::theme::ColorfulTheme;
+use std::io::IsTerminal;
 
 pub struct Dialoguer;
 
@@ -11,6 +12,11 @@ impl Dialoguer {
 pub trait TDialoguer: Send + Sync {
     fn prompt(&self, message: &str) -> String;
     fn confirm(&self, message: &str) -> bool;
+    /// Hidden input, asked twice when `confirm` is set
+    fn password(&self, message: &str, confirm: bool) -> String;
+    /// Index of the chosen item
+    fn select(&self, message: &str, items: &[String]) -> usize;
+    fn is_interactive(&self) -> bool;
 }
 
 impl TDialoguer for Dialoguer {
@@ -27,4 +33,27 @@ impl TDialoguer for Dialoguer {
             .interact()
             .unwrap()
     }
+
+    fn password(&self, message: &str, confirm: bool) -> String {
+        let theme = ColorfulTheme::default();
+        let mut password = dialoguer::Password::with_theme(&theme).with_prompt(message);
+        if confirm {
+            password = password.with_confirmation("Repeat password", "Passwords don't match");
+        }
+
+        password.interact().unwrap()
+    }
+
+    fn select(&self, message: &str, items: &[String]) -> usize {
+        dialoguer::Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(message)
+            .items(items)
+            .default(0)
+            .interact()
+            .unwrap()
+    }
+
+    fn is_interactive(&self) -> bool {
+        std::io::stdin().is_terminal()
+    }
 }
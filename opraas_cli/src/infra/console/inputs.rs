@@ -0,0 +1,128 @@
+use super::{print_warning, TDialoguer};
+use opraas_core::{application::TReleaseCatalogService, config::AccountsConfig};
+use std::path::Path;
+
+/// A value a command would otherwise prompt for, settable through a flag or its env var
+#[derive(Debug)]
+pub struct Input<'a> {
+    pub flag: &'a str,
+    pub env: &'a str,
+}
+
+// inputs constants ======================================
+
+pub const REGISTRY_INPUT: Input = Input {
+    flag: "--registry",
+    env: "OPRUAAS_REGISTRY",
+};
+pub const RELEASE_INPUT: Input = Input {
+    flag: "--release",
+    env: "OPRUAAS_RELEASE",
+};
+pub const KEYSTORE_PASSWORD_INPUT: Input = Input {
+    flag: "--keystore-password",
+    env: "OPRUAAS_KEYSTORE_PASSWORD",
+};
+pub const BUNDLE_PASSPHRASE_INPUT: Input = Input {
+    flag: "--passphrase",
+    env: "OPRUAAS_BUNDLE_PASSPHRASE",
+};
+pub const YES_INPUT: Input = Input {
+    flag: "--yes",
+    env: "OPRUAAS_YES",
+};
+
+// implementations ================================================
+
+/// Fails listing every input that wasn't provided when there's no terminal to prompt for them.
+/// Each input comes paired with whether it was provided.
+pub fn require_inputs(
+    dialoguer: &dyn TDialoguer,
+    inputs: Vec<(Input, bool)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if dialoguer.is_interactive() {
+        return Ok(());
+    }
+
+    let missing: Vec<String> = inputs
+        .iter()
+        .filter(|(_, provided)| !provided)
+        .map(|(input, _)| format!("  {} (or {})", input.flag, input.env))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "stdin is not a terminal so we can't prompt, please provide:\n{}",
+        missing.join("\n")
+    )
+    .into())
+}
+
+/// Offers the releases found in the registry, incomplete ones tagged with their missing artifacts. Falls back to
+/// typing the name when the registry can't be listed or has none.
+pub fn prompt_release_name(
+    dialoguer: &dyn TDialoguer,
+    release_catalog: &dyn TReleaseCatalogService,
+    registry_url: &str,
+) -> String {
+    let releases = match release_catalog.list(registry_url) {
+        Ok(releases) => releases,
+        Err(e) => {
+            print_warning(&format!(
+                "Couldn't list the releases in {}: {}",
+                registry_url, e
+            ));
+            vec![]
+        }
+    };
+    if releases.is_empty() {
+        return dialoguer.prompt("Input release name (e.g. v0.1.0)");
+    }
+
+    let mut items: Vec<String> = releases
+        .iter()
+        .map(|release| match release.is_complete() {
+            true => release.name.clone(),
+            false => format!(
+                "{} (missing {})",
+                release.name,
+                release.missing().join(", ")
+            ),
+        })
+        .collect();
+    items.push("Other...".to_string());
+
+    match releases.get(dialoguer.select("Select a release", &items)) {
+        Some(release) => release.name.clone(),
+        None => dialoguer.prompt("Input release name (e.g. v0.1.0)"),
+    }
+}
+
+/// Exports the private keys kept in the keystores at `keystore_dir` when the env doesn't have them, reading the
+/// password from OPRUAAS_KEYSTORE_PASSWORD or asking for it.
+pub fn unlock_keystores(dialoguer: &dyn TDialoguer, keystore_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let locked_roles = AccountsConfig::locked_roles(keystore_dir);
+    if locked_roles.is_empty() {
+        return Ok(());
+    }
+
+    let password = match std::env::var(KEYSTORE_PASSWORD_INPUT.env) {
+        Ok(password) => password,
+        Err(_) if dialoguer.is_interactive() => dialoguer.password(
+            &format!("Keystore password for {}", locked_roles.join(", ")),
+            false,
+        ),
+        Err(_) => {
+            return Err(format!(
+                "Private keys of {} are in keystores, set {} to unlock them",
+                locked_roles.join(", "),
+                KEYSTORE_PASSWORD_INPUT.env
+            )
+            .into())
+        }
+    };
+
+    AccountsConfig::unlock_keystores(keystore_dir, &password)
+}
@@ -1,7 +1,9 @@
 pub mod dialoguer;
+pub mod inputs;
 pub mod progress;
 pub mod terminal;
 
 pub use dialoguer::*;
+pub use inputs::*;
 pub use progress::*;
 pub use terminal::*;
@@ -9,3 +9,16 @@ pub fn style_spinner(spinner: ProgressBar, message: &str) -> ProgressBar {
 
     spinner
 }
+
+/// Bar for downloads, fill it with set_length and set_position and put the transferred size in the message
+pub fn style_transfer_bar(bar: ProgressBar, prefix: &str) -> ProgressBar {
+    bar.set_style(
+        ProgressStyle::with_template("{spinner:.blue} {prefix:<10} [{bar:30.blue}] {pos}/{len} objects {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar.set_prefix(prefix.to_string());
+    bar.enable_steady_tick(Duration::from_millis(100));
+
+    bar
+}
@@ -5,14 +5,22 @@ mod infra;
 use build::BuildTargets;
 use clap::{Parser, Subcommand};
 use colored::Colorize;
+use commands::config::ConfigSubcommand;
 use commands::*;
 use deploy::DeployTarget;
+use deployments::DeploymentsSubcommand;
+use dev::{AnvilFlags, DevL1, DevSubcommand};
 use dotenv::dotenv;
 use infra::console::print_error;
 use init::InitTargets;
 use inspect::InspectTarget;
+use keys::KeysSubcommand;
 use log::{Level, LevelFilter};
-use release::ReleaseTargets;
+use logs::LogsTarget;
+use monitor::MonitorTarget;
+use release::{ReleaseSubcommand, ReleaseTargets};
+use std::path::PathBuf;
+use upgrade::UpgradeTarget;
 
 #[derive(Parser)]
 #[clap(name = "opruaas")]
@@ -32,15 +40,102 @@ enum Commands {
     /// Create new project, template config file and folders
     New { name: String },
     /// Initialize a new project
-    Init { target: InitTargets },
+    Init {
+        target: InitTargets,
+
+        /// Move existing sources to the configured refs instead of skipping them
+        #[arg(long, default_value_t = false)]
+        update: bool,
+
+        /// Discard uncommitted changes in the sources being updated
+        #[arg(long, default_value_t = false, requires = "update")]
+        force: bool,
+    },
     /// Compile sources and create docker images for it
-    Build { target: BuildTargets },
+    Build {
+        target: BuildTargets,
+
+        /// Build for these platforms with docker buildx, e.g. linux/amd64,linux/arm64
+        #[arg(long, value_delimiter = ',')]
+        platform: Vec<String>,
+
+        /// Rebuild even if the local image was built from the same sources
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
     /// Tags and pushes already built docker images to the registry for usage in the deployment
-    Release { target: ReleaseTargets },
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Release {
+        #[command(subcommand)]
+        cmd: Option<ReleaseSubcommand>,
+
+        #[arg(required = true)]
+        target: Option<ReleaseTargets>,
+
+        #[arg(long, env = "OPRUAAS_REGISTRY")]
+        registry: Option<String>,
+
+        #[arg(long, env = "OPRUAAS_RELEASE")]
+        release: Option<String>,
+
+        /// Push a multi-platform manifest list with docker buildx, e.g. linux/amd64,linux/arm64
+        #[arg(long, value_delimiter = ',')]
+        platform: Vec<String>,
+
+        /// Also tag the local git repository with the release name
+        #[arg(long, env = "OPRUAAS_GIT_TAG", default_value_t = false)]
+        git_tag: bool,
+
+        /// Don't ask for confirmation
+        #[arg(long, env = "OPRUAAS_YES", default_value_t = false)]
+        yes: bool,
+    },
     /// Spin up local dev environment
+    #[command(args_conflicts_with_subcommands = true)]
     Dev {
+        #[command(subcommand)]
+        cmd: Option<DevSubcommand>,
+
         #[arg(long, default_value_t = false)]
         default: bool,
+
+        #[arg(long, env = "OPRUAAS_REGISTRY")]
+        registry: Option<String>,
+
+        #[arg(long, env = "OPRUAAS_RELEASE")]
+        release: Option<String>,
+
+        /// Don't ask for confirmation
+        #[arg(long, env = "OPRUAAS_YES", default_value_t = false)]
+        yes: bool,
+
+        /// Run the images built with the build command, through a throwaway local registry
+        #[arg(long, default_value_t = false, conflicts_with_all = ["registry", "release"])]
+        local_images: bool,
+
+        /// L1 node to run the devnet on
+        #[arg(long, value_enum, default_value_t = DevL1::Geth)]
+        l1: DevL1,
+
+        /// Anvil only, load the L1 state from this file when it exists and dump it back on stop
+        #[arg(long)]
+        l1_state: Option<PathBuf>,
+
+        /// Anvil only, seconds between L1 blocks [default: 1]
+        #[arg(long)]
+        block_time: Option<u64>,
+
+        /// Anvil only, number of pre-funded L1 accounts [default: 30]
+        #[arg(long)]
+        accounts: Option<u32>,
+
+        /// Anvil only, ether each pre-funded account starts with [default: 10000]
+        #[arg(long)]
+        balance: Option<u64>,
+
+        /// Leave the devnet running in the background, manage it with dev status and dev stop
+        #[arg(long, default_value_t = false)]
+        detach: bool,
     },
     /// Deploy your blockchain. Target must be one of: contracts, infra, all
     Deploy {
@@ -51,6 +146,58 @@ enum Commands {
 
         #[arg(long, default_value_t = false)]
         deterministic_deployer: bool,
+
+        #[arg(long, env = "OPRUAAS_REGISTRY")]
+        registry: Option<String>,
+
+        #[arg(long, env = "OPRUAAS_RELEASE")]
+        release: Option<String>,
+
+        /// Don't ask for confirmation
+        #[arg(long, env = "OPRUAAS_YES", default_value_t = false)]
+        yes: bool,
+    },
+    /// Tear down a deployment's infra and archive its records
+    Destroy {
+        #[arg(long)]
+        name: String,
+
+        #[arg(long, default_value_t = false)]
+        keep_records: bool,
+
+        /// Don't ask for confirmation
+        #[arg(long, env = "OPRUAAS_YES", default_value_t = false)]
+        yes: bool,
+    },
+    /// Roll a deployment to a new release. Use --only to upgrade some components: node, geth, batcher, proposer, explorer
+    Upgrade {
+        #[arg(long)]
+        name: String,
+
+        #[arg(long)]
+        release: String,
+
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<UpgradeTarget>,
+
+        /// Don't ask for confirmation
+        #[arg(long, env = "OPRUAAS_YES", default_value_t = false)]
+        yes: bool,
+    },
+    /// Check the project config
+    Config {
+        #[command(subcommand)]
+        cmd: ConfigSubcommand,
+    },
+    /// Manage the project accounts keys
+    Keys {
+        #[command(subcommand)]
+        cmd: KeysSubcommand,
+    },
+    /// Manage the deployments of the project
+    Deployments {
+        #[command(subcommand)]
+        cmd: DeploymentsSubcommand,
     },
     /// Get details about the current deployment. Target must be one of: contracts, infra
     Inspect {
@@ -59,8 +206,61 @@ enum Commands {
         #[arg(long)]
         deployment: String,
     },
-    // /// Monitor your chain. Target must be one of: onchain, offchain
-    // Monitor { target: MonitorTarget },
+    /// Diagnose your environment: tools, docker, kubernetes, helm, aws, resources and .env
+    Doctor {
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Check the sync status of a deployment through its op-node, op-geth and L1 rpcs
+    Status {
+        #[arg(long)]
+        name: String,
+
+        /// Defaults to the L1 rpc of the deployment
+        #[arg(long)]
+        l1_rpc: Option<String>,
+
+        /// Defaults to the chain ingress from the infra artifacts
+        #[arg(long)]
+        l2_rpc: Option<String>,
+
+        #[arg(long)]
+        node_rpc: Option<String>,
+
+        #[arg(long, default_value_t = false)]
+        json: bool,
+    },
+    /// Stream the logs of a component's pods. Component must be one of: node, geth, batcher, proposer, explorer
+    Logs {
+        #[arg(required_unless_present = "all")]
+        component: Option<LogsTarget>,
+
+        /// Deployment to read the logs from, dev for the local devnet
+        #[arg(long, default_value = "dev")]
+        name: String,
+
+        #[arg(long, short, default_value_t = false)]
+        follow: bool,
+
+        /// Only logs newer than a relative duration like 5s, 2m or 3h
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Interleave the logs of every component
+        #[arg(long, default_value_t = false, conflicts_with = "component")]
+        all: bool,
+    },
+    /// Monitor your chain. Target must be one of: onchain
+    Monitor {
+        target: MonitorTarget,
+
+        #[arg(long)]
+        name: String,
+
+        /// Seconds between polls
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+    },
 }
 
 #[tokio::main]
@@ -96,17 +296,93 @@ async fn main() {
     // run commands
     if let Err(e) = match args.cmd {
         Commands::New { name } => NewCommand::new().run(name),
-        Commands::Init { target } => InitCommand::new().run(target),
-        Commands::Build { target } => BuildCommand::new().run(target),
-        Commands::Release { target } => ReleaseCommand::new().run(target),
-        Commands::Dev { default } => DevCommand::new().run(default),
+        Commands::Init {
+            target,
+            update,
+            force,
+        } => InitCommand::new().run(target, update, force),
+        Commands::Build {
+            target,
+            platform,
+            force,
+        } => BuildCommand::new().run(target, platform, force),
+        Commands::Release {
+            cmd,
+            target,
+            registry,
+            release,
+            platform,
+            git_tag,
+            yes,
+        } => ReleaseCommand::new().run(cmd, target, registry, release, platform, git_tag, yes),
+        Commands::Dev {
+            cmd,
+            default,
+            registry,
+            release,
+            yes,
+            local_images,
+            l1,
+            l1_state,
+            block_time,
+            accounts,
+            balance,
+            detach,
+        } => DevCommand::new().run(
+            cmd,
+            default,
+            registry,
+            release,
+            local_images,
+            l1,
+            AnvilFlags {
+                state: l1_state,
+                block_time,
+                accounts,
+                balance,
+            },
+            detach,
+            yes,
+        ),
         Commands::Deploy {
             target,
             name,
             deterministic_deployer,
-        } => DeployCommand::new().run(target, name, deterministic_deployer),
+            registry,
+            release,
+            yes,
+        } => DeployCommand::new().run(target, name, deterministic_deployer, registry, release, yes),
+        Commands::Destroy {
+            name,
+            keep_records,
+            yes,
+        } => DestroyCommand::new().run(name, keep_records, yes),
+        Commands::Upgrade {
+            name,
+            release,
+            only,
+            yes,
+        } => UpgradeCommand::new().run(name, release, only, yes),
+        Commands::Config { cmd } => ConfigCommand::new().run(cmd),
+        Commands::Keys { cmd } => KeysCommand::new().run(cmd),
+        Commands::Deployments { cmd } => DeploymentsCommand::new().run(cmd),
         Commands::Inspect { target, deployment } => InspectCommand::new().run(target, deployment),
-        // Commands::Monitor { target } => MonitorCommand::new(target).run(&config).await,
+        Commands::Doctor { json } => DoctorCommand::new().run(json),
+        Commands::Status {
+            name,
+            l1_rpc,
+            l2_rpc,
+            node_rpc,
+            json,
+        } => StatusCommand::new().run(name, l1_rpc, l2_rpc, node_rpc, json),
+        Commands::Logs {
+            component,
+            name,
+            follow,
+            since,
+            all,
+        } => LogsCommand::new().run(component, name, follow, since, all),
+        Commands::Monitor { target, name, interval } => MonitorCommand::new().run(target, name, interval),
     } {
         print_error(&format!("\n\nError: {}\n\n", e));
         std::process::exit(1);
@@ -0,0 +1,227 @@
+use crate::{
+    config::AccountsConfig,
+    domain::{Project, TProjectRepository},
+    infra::ethereum::Wallet,
+};
+use regex::Regex;
+
+pub struct AccountsGeneratorService {
+    repository: Box<dyn TProjectRepository>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratedAccount {
+    pub role: &'static str,
+    pub wallet: Wallet,
+    /// set when derived from a mnemonic
+    pub derivation_path: Option<String>,
+}
+
+pub trait TAccountsGeneratorService: Send + Sync {
+    /// One account per role, random or derived from `mnemonic` at `{derivation_path}/{index}`
+    fn generate(
+        &self,
+        mnemonic: Option<&str>,
+        derivation_path: &str,
+    ) -> Result<Vec<GeneratedAccount>, Box<dyn std::error::Error>>;
+
+    /// Writes addresses to .env and config.toml. Private keys go to .env, or to encrypted keystores
+    /// under `keystore/` when a password is given.
+    fn save(
+        &self,
+        project: &Project,
+        accounts: &[GeneratedAccount],
+        keystore_password: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+// implementations ===================================================
+
+impl AccountsGeneratorService {
+    pub fn new(repository: Box<dyn TProjectRepository>) -> Self {
+        Self { repository }
+    }
+}
+
+impl TAccountsGeneratorService for AccountsGeneratorService {
+    fn generate(
+        &self,
+        mnemonic: Option<&str>,
+        derivation_path: &str,
+    ) -> Result<Vec<GeneratedAccount>, Box<dyn std::error::Error>> {
+        AccountsConfig::ROLES
+            .iter()
+            .enumerate()
+            .map(|(index, role)| match mnemonic {
+                Some(mnemonic) => {
+                    let path = format!("{}/{}", derivation_path.trim_end_matches('/'), index);
+
+                    Ok(GeneratedAccount {
+                        role,
+                        wallet: Wallet::from_mnemonic(mnemonic, &path)?,
+                        derivation_path: Some(path),
+                    })
+                }
+                None => Ok(GeneratedAccount {
+                    role,
+                    wallet: Wallet::random(),
+                    derivation_path: None,
+                }),
+            })
+            .collect()
+    }
+
+    fn save(
+        &self,
+        project: &Project,
+        accounts: &[GeneratedAccount],
+        keystore_password: Option<&str>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let env_path = project.root.join(".env");
+        let mut env = std::fs::read_to_string(&env_path).unwrap_or_default();
+        let mut config = std::fs::read_to_string(&project.config).unwrap_or_default();
+
+        for account in accounts {
+            let role = account.role.to_uppercase();
+
+            env = set_env_var(
+                &env,
+                &format!("{}_ADDRESS", role),
+                Some(&account.wallet.address),
+            );
+            match keystore_password {
+                Some(password) => {
+                    account.wallet.encrypt(
+                        &project.root.join("keystore"),
+                        password,
+                        &format!("{}.json", account.role),
+                    )?;
+                    env = set_env_var(&env, &format!("{}_PRIVATE_KEY", role), None);
+                }
+                None => {
+                    env = set_env_var(
+                        &env,
+                        &format!("{}_PRIVATE_KEY", role),
+                        Some(&account.wallet.private_key),
+                    );
+                }
+            }
+
+            // config.toml addresses take precedence over the env ones
+            let address_field = Regex::new(&format!(
+                r#"(?m)^(\s*{}_address\s*=\s*)"[^"]*""#,
+                account.role
+            ))?;
+            config = address_field
+                .replace(&config, format!(r#"${{1}}"{}""#, account.wallet.address))
+                .to_string();
+        }
+
+        self.repository.write(project, &env_path, &env)?;
+        if project.config.exists() {
+            self.repository.write(project, &project.config, &config)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces the line defining `key` with `key="value"`, appending it when missing. Removes it when `value` is None.
+fn set_env_var(env: &str, key: &str, value: Option<&str>) -> String {
+    let is_key = |line: &&str| {
+        line.trim_start()
+            .split_once('=')
+            .is_some_and(|(k, _)| k.trim() == key)
+    };
+    let line = value.map(|value| format!("{}=\"{}\"", key, value));
+
+    let mut lines: Vec<String> = vec![];
+    let mut replaced = false;
+    for existing in env.lines() {
+        if !is_key(&existing) {
+            lines.push(existing.to_string());
+        } else if let (Some(line), false) = (&line, replaced) {
+            lines.push(line.clone());
+            replaced = true;
+        }
+    }
+    if let (Some(line), false) = (line, replaced) {
+        lines.push(line);
+    }
+
+    format!("{}\n", lines.join("\n").trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_env_var, AccountsGeneratorService, TAccountsGeneratorService};
+    use crate::{
+        domain::{ProjectFactory, TProjectFactory},
+        infra::{ethereum::DEFAULT_DERIVATION_PATH, project::InMemoryProjectRepository},
+    };
+
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn should_derive_one_account_per_role_from_mnemonic() {
+        let service = AccountsGeneratorService::new(Box::new(InMemoryProjectRepository::new()));
+
+        let accounts = service
+            .generate(Some(TEST_MNEMONIC), DEFAULT_DERIVATION_PATH)
+            .unwrap();
+
+        assert_eq!(accounts.len(), 6);
+        assert_eq!(accounts[0].role, "admin");
+        assert_eq!(
+            accounts[0].wallet.address,
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert_eq!(accounts[1].role, "batcher");
+        assert_eq!(
+            accounts[1].wallet.address,
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+        );
+        assert_eq!(
+            accounts[5].derivation_path.as_deref(),
+            Some("m/44'/60'/0'/0/5")
+        );
+    }
+
+    #[test]
+    fn should_write_accounts_to_env_and_config() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let project = ProjectFactory::new().from_root(dir.path().to_path_buf());
+        std::fs::write(
+            dir.path().join(".env"),
+            "L1_RPC_URL=\"http://localhost:8545\"\nADMIN_PRIVATE_KEY=\"0x00\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &project.config,
+            "[accounts]\nadmin_address = \"0x0000000000000000000000000000000000000000\"\n",
+        )
+        .unwrap();
+
+        let service = AccountsGeneratorService::new(Box::new(InMemoryProjectRepository::new()));
+        let accounts = service
+            .generate(Some(TEST_MNEMONIC), DEFAULT_DERIVATION_PATH)
+            .unwrap();
+        service.save(&project, &accounts, None).unwrap();
+
+        let env = std::fs::read_to_string(dir.path().join(".env")).unwrap();
+        assert!(env.starts_with("L1_RPC_URL=\"http://localhost:8545\"\nADMIN_PRIVATE_KEY=\"0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80\"\n"));
+        assert!(env.contains("ADMIN_ADDRESS=\"0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266\"\n"));
+        assert!(env.contains("CHALLENGER_PRIVATE_KEY="));
+
+        let config = std::fs::read_to_string(&project.config).unwrap();
+        assert_eq!(
+            config,
+            "[accounts]\nadmin_address = \"0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266\"\n"
+        );
+    }
+
+    #[test]
+    fn should_remove_env_var_when_no_value() {
+        assert_eq!(set_env_var("A=\"1\"\nB=\"2\"\n", "A", None), "B=\"2\"\n");
+    }
+}
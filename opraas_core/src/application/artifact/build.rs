@@ -1,12 +1,26 @@
 use crate::domain::{self, artifact::Artifact};
+use sha2::{Digest, Sha256};
 
 pub struct ArtifactBuilderService {
     artifact_repository: Box<dyn domain::artifact::TArtifactRepository>,
     artifact_source_repository: Box<dyn domain::artifact::TArtifactSourceRepository>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildOutcome {
+    Built,
+    /// the local image already matches the fingerprint
+    Cached,
+}
+
 pub trait TArtifactBuilderService: Send + Sync {
-    fn build(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>>;
+    /// Builds the artifact unless the local image has the same fingerprint, `force` always builds
+    fn build(
+        &self,
+        artifact: &Artifact,
+        platforms: &[String],
+        force: bool,
+    ) -> Result<BuildOutcome, Box<dyn std::error::Error>>;
 }
 
 // implementations ======================================================
@@ -21,55 +35,95 @@ impl ArtifactBuilderService {
             artifact_source_repository,
         }
     }
+
+    // hash of the source tree state, the dockerfile and the build platforms
+    fn fingerprint(&self, artifact: &Artifact, platforms: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.artifact_source_repository.tree_state(artifact)?);
+        hasher.update(b"\n");
+        hasher.update(std::fs::read(artifact.dockerfile())?);
+        hasher.update(b"\n");
+        hasher.update(platforms.join(","));
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
 }
 
 impl TArtifactBuilderService for ArtifactBuilderService {
-    fn build(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>> {
+    fn build(
+        &self,
+        artifact: &Artifact,
+        platforms: &[String],
+        force: bool,
+    ) -> Result<BuildOutcome, Box<dyn std::error::Error>> {
         if !self.artifact_source_repository.exists(artifact) {
-            self.artifact_source_repository.pull(artifact)?;
+            self.artifact_source_repository.pull(artifact, &|_| {})?;
+        }
+
+        let fingerprint = self.fingerprint(artifact, platforms)?;
+        if !force && self.artifact_repository.fingerprint(artifact).as_deref() == Some(fingerprint.as_str()) {
+            return Ok(BuildOutcome::Cached);
         }
 
-        self.artifact_repository.create(artifact)?;
+        self.artifact_repository
+            .create(artifact, platforms, &fingerprint)?;
 
-        Ok(())
+        Ok(BuildOutcome::Built)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TArtifactBuilderService;
+    use super::{BuildOutcome, TArtifactBuilderService};
     use crate::{
         application::ArtifactBuilderService,
         domain::{Artifact, ArtifactData, MockTArtifactRepository, MockTArtifactSourceRepository},
     };
     use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn artifact(dockerfile: &NamedTempFile) -> Artifact {
+        Artifact::Batcher(ArtifactData {
+            name: "mock".to_string(),
+            context: PathBuf::new(),
+            dockerfile: dockerfile.path().to_path_buf(),
+            source_ref: "v0.0.1".to_string(),
+            source_path: None,
+            source_url: "http://github.com".to_string(),
+        })
+    }
 
     #[test]
     fn should_pull_source_if_not_existent() {
         let mut mock_artifact_repo = MockTArtifactRepository::new();
         let mut mock_source_repo = MockTArtifactSourceRepository::new();
 
-        let artifact = Artifact::Batcher(ArtifactData {
-            name: "mock".to_string(),
-            context: PathBuf::new(),
-            dockerfile: PathBuf::new(),
-            source_tag: "v0.0.1".to_string(),
-            source_url: "http://github.com".to_string(),
-        });
+        let dockerfile = NamedTempFile::new().unwrap();
+        let artifact = artifact(&dockerfile);
 
         // Mock behaviors
         mock_source_repo.expect_exists().return_const(false);
 
-        mock_source_repo.expect_pull().returning(|_| Ok(()));
+        mock_source_repo.expect_pull().returning(|_, _| Ok(()));
+        mock_source_repo
+            .expect_tree_state()
+            .returning(|_| Ok("commit".to_string()));
 
-        mock_artifact_repo.expect_create().returning(|_| Ok(()));
+        mock_artifact_repo.expect_fingerprint().return_const(None);
+        mock_artifact_repo
+            .expect_create()
+            .returning(|_, _, _| Ok(()));
 
         let service = ArtifactBuilderService {
             artifact_repository: Box::new(mock_artifact_repo),
             artifact_source_repository: Box::new(mock_source_repo),
         };
 
-        let result = service.build(&artifact);
+        let result = service.build(&artifact, &[], false);
         assert!(result.is_ok());
     }
 
@@ -78,27 +132,76 @@ mod tests {
         let mut mock_artifact_repo = MockTArtifactRepository::new();
         let mut mock_source_repo = MockTArtifactSourceRepository::new();
 
-        let artifact = Artifact::Batcher(ArtifactData {
-            name: "mock".to_string(),
-            context: PathBuf::new(),
-            dockerfile: PathBuf::new(),
-            source_tag: "v0.0.1".to_string(),
-            source_url: "http://github.com".to_string(),
-        });
+        let dockerfile = NamedTempFile::new().unwrap();
+        let artifact = artifact(&dockerfile);
 
         // Mock behaviors
         mock_source_repo.expect_exists().return_const(true);
 
         mock_source_repo.expect_pull().never();
+        mock_source_repo
+            .expect_tree_state()
+            .returning(|_| Ok("commit".to_string()));
 
-        mock_artifact_repo.expect_create().returning(|_| Ok(()));
+        mock_artifact_repo.expect_fingerprint().return_const(None);
+        mock_artifact_repo
+            .expect_create()
+            .returning(|_, _, _| Ok(()));
 
         let service = ArtifactBuilderService {
             artifact_repository: Box::new(mock_artifact_repo),
             artifact_source_repository: Box::new(mock_source_repo),
         };
 
-        let result = service.build(&artifact);
+        let result = service.build(&artifact, &[], false);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn should_skip_build_when_fingerprint_matches_unless_forced() {
+        let dockerfile = NamedTempFile::new().unwrap();
+        let artifact = artifact(&dockerfile);
+
+        let fingerprint = |tree_state: &str| {
+            let mut mock_source_repo = MockTArtifactSourceRepository::new();
+            mock_source_repo.expect_exists().return_const(true);
+            let tree_state = tree_state.to_string();
+            mock_source_repo
+                .expect_tree_state()
+                .returning(move |_| Ok(tree_state.clone()));
+
+            ArtifactBuilderService {
+                artifact_repository: Box::new(MockTArtifactRepository::new()),
+                artifact_source_repository: Box::new(mock_source_repo),
+            }
+            .fingerprint(&artifact, &[])
+            .unwrap()
+        };
+        let built_fingerprint = fingerprint("commit");
+        assert_ne!(built_fingerprint, fingerprint("commit\nsrc/main.go abc"));
+
+        for (force, outcome) in [(false, BuildOutcome::Cached), (true, BuildOutcome::Built)] {
+            let mut mock_artifact_repo = MockTArtifactRepository::new();
+            let mut mock_source_repo = MockTArtifactSourceRepository::new();
+
+            mock_source_repo.expect_exists().return_const(true);
+            mock_source_repo
+                .expect_tree_state()
+                .returning(|_| Ok("commit".to_string()));
+            mock_artifact_repo
+                .expect_fingerprint()
+                .return_const(Some(built_fingerprint.clone()));
+            mock_artifact_repo
+                .expect_create()
+                .times(if force { 1 } else { 0 })
+                .returning(|_, _, _| Ok(()));
+
+            let service = ArtifactBuilderService {
+                artifact_repository: Box::new(mock_artifact_repo),
+                artifact_source_repository: Box::new(mock_source_repo),
+            };
+
+            assert_eq!(service.build(&artifact, &[], force).unwrap(), outcome);
+        }
+    }
 }
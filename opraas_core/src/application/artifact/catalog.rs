@@ -0,0 +1,170 @@
+use crate::domain::{self, ArtifactKind, Release};
+use semver::Version;
+use std::collections::BTreeMap;
+
+pub struct ReleaseCatalogService {
+    release_registry: Box<dyn domain::release::TReleaseRegistry>,
+}
+
+/// A release name found in the registry and the artifacts pushed under it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvailableRelease {
+    pub name: String,
+    pub artifacts: Vec<String>,
+}
+
+pub trait TReleaseCatalogService: Send + Sync {
+    /// Releases pushed to the registry for any artifact, newest versions first
+    fn list(&self, registry_url: &str) -> Result<Vec<AvailableRelease>, Box<dyn std::error::Error>>;
+    /// Releases the registry has no image for
+    fn missing(&self, releases: &[Release]) -> Result<Vec<Release>, Box<dyn std::error::Error>>;
+}
+
+// implementations ======================================================
+
+impl AvailableRelease {
+    /// Whether every artifact a deployment needs has an image for this release
+    pub fn is_complete(&self) -> bool {
+        self.missing().is_empty()
+    }
+
+    /// Artifacts a deployment needs that have no image for this release. The explorer isn't one of them, releases
+    /// without it run the upstream blockscout.
+    pub fn missing(&self) -> Vec<&'static str> {
+        ArtifactKind::all()
+            .iter()
+            .filter(|kind| !matches!(kind, ArtifactKind::Explorer))
+            .map(|kind| kind.artifact_name())
+            .filter(|artifact_name| {
+                !self
+                    .artifacts
+                    .iter()
+                    .any(|artifact| artifact == artifact_name)
+            })
+            .collect()
+    }
+}
+
+impl ReleaseCatalogService {
+    pub fn new(release_registry: Box<dyn domain::release::TReleaseRegistry>) -> Self {
+        Self { release_registry }
+    }
+}
+
+impl TReleaseCatalogService for ReleaseCatalogService {
+    fn list(&self, registry_url: &str) -> Result<Vec<AvailableRelease>, Box<dyn std::error::Error>> {
+        let mut releases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for kind in ArtifactKind::all() {
+            for tag in self
+                .release_registry
+                .tags(registry_url, kind.artifact_name())?
+            {
+                releases
+                    .entry(tag)
+                    .or_default()
+                    .push(kind.artifact_name().to_string());
+            }
+        }
+
+        let mut releases: Vec<AvailableRelease> = releases
+            .into_iter()
+            .map(|(name, artifacts)| AvailableRelease { name, artifacts })
+            .collect();
+
+        // versions like v0.1.0 sort by semver, anything else after them in reverse alphabetical order
+        releases.sort_by(|a, b| {
+            match (
+                Version::parse(a.name.trim_start_matches('v')),
+                Version::parse(b.name.trim_start_matches('v')),
+            ) {
+                (Ok(a), Ok(b)) => b.cmp(&a),
+                (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+                (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+                (Err(_), Err(_)) => b.name.cmp(&a.name),
+            }
+        });
+
+        Ok(releases)
+    }
+
+    fn missing(&self, releases: &[Release]) -> Result<Vec<Release>, Box<dyn std::error::Error>> {
+        let mut missing = vec![];
+        for release in releases {
+            if !self.release_registry.exists(release)? {
+                missing.push(release.clone());
+            }
+        }
+
+        Ok(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReleaseCatalogService, TReleaseCatalogService};
+    use crate::domain::{MockTReleaseRegistry, Release};
+
+    #[test]
+    fn lists_releases_marking_complete_ones() {
+        let mut mock_release_registry = MockTReleaseRegistry::new();
+
+        mock_release_registry
+            .expect_tags()
+            .returning(|_, artifact_name| match artifact_name {
+                "op-node" => Ok(vec![
+                    "v0.2.0".to_string(),
+                    "v0.10.0".to_string(),
+                    "latest".to_string(),
+                ]),
+                // older releases predate the explorer artifact
+                "op-explorer" => Ok(vec!["v0.10.0".to_string()]),
+                _ => Ok(vec!["v0.2.0".to_string(), "v0.10.0".to_string()]),
+            });
+
+        let service = ReleaseCatalogService {
+            release_registry: Box::new(mock_release_registry),
+        };
+
+        let releases = service.list("wakeuplabs").unwrap();
+        assert_eq!(
+            releases
+                .iter()
+                .map(|release| release.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["v0.10.0", "v0.2.0", "latest"]
+        );
+        assert!(releases[0].is_complete());
+        assert!(releases[1].is_complete());
+        assert!(!releases[2].is_complete());
+        assert_eq!(releases[2].missing().len(), 4);
+    }
+
+    #[test]
+    fn finds_missing_releases() {
+        let mut mock_release_registry = MockTReleaseRegistry::new();
+
+        mock_release_registry
+            .expect_exists()
+            .times(3)
+            .returning(|release| Ok(release.artifact_name != "op-geth"));
+
+        let service = ReleaseCatalogService {
+            release_registry: Box::new(mock_release_registry),
+        };
+
+        let releases: Vec<Release> = ["op-node", "op-geth", "op-batcher"]
+            .iter()
+            .map(|artifact_name| {
+                Release::new(
+                    artifact_name.to_string(),
+                    "v0.1.0".to_string(),
+                    "wakeuplabs".to_string(),
+                )
+            })
+            .collect();
+
+        let missing = service.missing(&releases).unwrap();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].artifact_name, "op-geth");
+    }
+}
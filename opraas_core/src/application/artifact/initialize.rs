@@ -1,12 +1,26 @@
 use crate::domain;
-use crate::domain::artifact::Artifact;
+use crate::domain::artifact::{Artifact, OnPullProgress};
 
 pub struct ArtifactInitializer {
     source_repository: Box<dyn domain::artifact::TArtifactSourceRepository>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceUpdate {
+    /// commit the source was at, none if it wasn't pulled yet
+    pub previous_commit: Option<String>,
+    pub commit: String,
+}
+
 pub trait TArtifactInitializerService: Send + Sync {
-    fn initialize(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>>;
+    fn initialize(&self, artifact: &Artifact, on_progress: &OnPullProgress) -> Result<(), Box<dyn std::error::Error>>;
+    /// Like initialize but existing sources are moved to the configured ref, `force` discards uncommitted changes
+    fn update(
+        &self,
+        artifact: &Artifact,
+        force: bool,
+        on_progress: &OnPullProgress,
+    ) -> Result<SourceUpdate, Box<dyn std::error::Error>>;
 }
 
 // implementations =================================================
@@ -18,21 +32,46 @@ impl ArtifactInitializer {
 }
 
 impl TArtifactInitializerService for ArtifactInitializer {
-    fn initialize(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>> {
+    fn initialize(&self, artifact: &Artifact, on_progress: &OnPullProgress) -> Result<(), Box<dyn std::error::Error>> {
         if self.source_repository.exists(artifact) {
             return Ok(());
         }
 
-        self.source_repository.pull(artifact)?;
+        self.source_repository.pull(artifact, on_progress)?;
 
         Ok(())
     }
+
+    fn update(
+        &self,
+        artifact: &Artifact,
+        force: bool,
+        on_progress: &OnPullProgress,
+    ) -> Result<SourceUpdate, Box<dyn std::error::Error>> {
+        if !self.source_repository.exists(artifact) {
+            self.source_repository.pull(artifact, on_progress)?;
+
+            return Ok(SourceUpdate {
+                previous_commit: None,
+                commit: self.source_repository.commit(artifact)?,
+            });
+        }
+
+        let previous_commit = self.source_repository.commit(artifact)?;
+        self.source_repository
+            .update(artifact, force, on_progress)?;
+
+        Ok(SourceUpdate {
+            previous_commit: Some(previous_commit),
+            commit: self.source_repository.commit(artifact)?,
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        application::{ArtifactInitializer, TArtifactInitializerService},
+        application::{ArtifactInitializer, SourceUpdate, TArtifactInitializerService},
         domain::{Artifact, ArtifactData, MockTArtifactSourceRepository},
     };
     use std::path::PathBuf;
@@ -45,7 +84,8 @@ mod tests {
             name: "mock".to_string(),
             context: PathBuf::new(),
             dockerfile: PathBuf::new(),
-            source_tag: "v0.0.1".to_string(),
+            source_ref: "v0.0.1".to_string(),
+            source_path: None,
             source_url: "http://github.com".to_string(),
         });
 
@@ -57,7 +97,7 @@ mod tests {
             source_repository: Box::new(mock_source_repo),
         };
 
-        let result = service.initialize(&artifact);
+        let result = service.initialize(&artifact, &|_| {});
         assert!(result.is_ok());
     }
 
@@ -69,19 +109,60 @@ mod tests {
             name: "mock".to_string(),
             context: PathBuf::new(),
             dockerfile: PathBuf::new(),
-            source_tag: "v0.0.1".to_string(),
+            source_ref: "v0.0.1".to_string(),
+            source_path: None,
             source_url: "http://github.com".to_string(),
         });
 
         mock_source_repo.expect_exists().returning(|_| false);
 
-        mock_source_repo.expect_pull().returning(|_| Ok(()));
+        mock_source_repo.expect_pull().returning(|_, _| Ok(()));
 
         let service = ArtifactInitializer {
             source_repository: Box::new(mock_source_repo),
         };
 
-        let result = service.initialize(&artifact);
+        let result = service.initialize(&artifact, &|_| {});
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn should_update_existent_sources_in_place() {
+        let mut mock_source_repo = MockTArtifactSourceRepository::new();
+
+        let artifact = Artifact::Batcher(ArtifactData {
+            name: "mock".to_string(),
+            context: PathBuf::new(),
+            dockerfile: PathBuf::new(),
+            source_ref: "v0.0.2".to_string(),
+            source_path: None,
+            source_url: "http://github.com".to_string(),
+        });
+
+        let mut commits = vec!["new".to_string(), "old".to_string()];
+        mock_source_repo.expect_exists().returning(|_| true);
+        mock_source_repo.expect_pull().never();
+        mock_source_repo
+            .expect_update()
+            .withf(|_, force, _| *force)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        mock_source_repo
+            .expect_commit()
+            .times(2)
+            .returning(move |_| Ok(commits.pop().unwrap()));
+
+        let service = ArtifactInitializer {
+            source_repository: Box::new(mock_source_repo),
+        };
+
+        let result = service.update(&artifact, true, &|_| {});
+        assert_eq!(
+            result.unwrap(),
+            SourceUpdate {
+                previous_commit: Some("old".to_string()),
+                commit: "new".to_string(),
+            }
+        );
+    }
 }
@@ -1,7 +1,9 @@
 pub mod build;
+pub mod catalog;
 pub mod initialize;
 pub mod release;
 
 pub use build::*;
+pub use catalog::*;
 pub use initialize::*;
 pub use release::*;
@@ -10,6 +10,15 @@ pub trait TArtifactReleaserService: Send + Sync {
         artifact: &Artifact,
         release_name: &str,
         registry_url: &str,
+        platforms: &[String],
+    ) -> Result<Release, Box<dyn std::error::Error>>;
+    /// Copies an existing release of the artifact to another registry or name without rebuilding it
+    fn promote(
+        &self,
+        artifact: &Artifact,
+        from: &Release,
+        registry_url: &str,
+        release_name: &str,
     ) -> Result<Release, Box<dyn std::error::Error>>;
 }
 
@@ -27,9 +36,25 @@ impl TArtifactReleaserService for ArtifactReleaserService {
         artifact: &Artifact,
         release_name: &str,
         registry_url: &str,
+        platforms: &[String],
+    ) -> Result<Release, Box<dyn std::error::Error>> {
+        self.release_repository
+            .create_for_artifact(&artifact, release_name, registry_url, platforms)
+    }
+
+    fn promote(
+        &self,
+        artifact: &Artifact,
+        from: &Release,
+        registry_url: &str,
+        release_name: &str,
     ) -> Result<Release, Box<dyn std::error::Error>> {
+        if from.registry_url == registry_url && from.artifact_tag == release_name {
+            return Err(format!("Can't promote {} onto itself", from.uri()).into());
+        }
+
         self.release_repository
-            .create_for_artifact(&artifact, release_name, registry_url)
+            .promote(artifact, from, registry_url, release_name)
     }
 }
 
@@ -47,17 +72,20 @@ mod test {
             name: "mock".to_string(),
             context: PathBuf::new(),
             dockerfile: PathBuf::new(),
-            source_tag: "v0.0.1".to_string(),
+            source_ref: "v0.0.1".to_string(),
+            source_path: None,
             source_url: "http://github.com".to_string(),
         });
 
         mock_release_repository
             .expect_create_for_artifact()
-            .returning(|_, _, _| {
+            .returning(|_, _, _, _| {
                 Ok(Release {
                     artifact_name: "artifact_name".to_string(),
                     artifact_tag: "artifact_tag".to_string(),
                     registry_url: "registry_url".to_string(),
+                    platforms: vec![],
+                    digest: None,
                 })
             });
 
@@ -65,7 +93,41 @@ mod test {
             release_repository: Box::new(mock_release_repository),
         };
 
-        let result = service.release(&artifact, "release_name", "wakeuplabs");
+        let result = service.release(&artifact, "release_name", "wakeuplabs", &[]);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn refuses_to_promote_a_release_onto_itself() {
+        let mut mock_release_repository = MockTReleaseRepository::new();
+
+        let artifact = Artifact::Node(ArtifactData {
+            name: "op-node".to_string(),
+            context: PathBuf::new(),
+            dockerfile: PathBuf::new(),
+            source_ref: "v0.0.1".to_string(),
+            source_path: None,
+            source_url: "http://github.com".to_string(),
+        });
+        let from = Release::from_artifact(&artifact, "v0.1.0", "staging");
+
+        mock_release_repository
+            .expect_promote()
+            .withf(|_, _, registry_url, release_name| registry_url == "production" && release_name == "v0.1.0")
+            .times(1)
+            .returning(|artifact, _, registry_url, release_name| {
+                Ok(Release::from_artifact(artifact, release_name, registry_url))
+            });
+
+        let service = ArtifactReleaserService {
+            release_repository: Box::new(mock_release_repository),
+        };
+
+        assert!(service
+            .promote(&artifact, &from, "staging", "v0.1.0")
+            .is_err());
+        assert!(service
+            .promote(&artifact, &from, "production", "v0.1.0")
+            .is_ok());
+    }
 }
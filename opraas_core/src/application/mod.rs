@@ -1,5 +1,7 @@
+pub mod accounts;
 pub mod artifact;
 pub mod contracts;
+pub mod monitor;
 pub mod project;
 pub mod stack;
 
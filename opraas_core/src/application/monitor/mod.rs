@@ -0,0 +1,5 @@
+pub mod onchain;
+pub mod status;
+
+pub use onchain::*;
+pub use status::*;
@@ -0,0 +1,276 @@
+use crate::{domain::Deployment, infra::ethereum::EthRpc};
+use serde_json::{json, Value};
+
+pub struct OnchainMonitorService {
+    rpc: Box<dyn EthRpc>,
+    l1_rpc_url: String,
+    batch_inbox_address: String,
+    output_submission_addresses: Vec<String>,
+    batch_threshold: u64,
+    proposal_threshold: u64,
+    lookback_blocks: u64,
+    last_scanned_block: Option<u64>,
+    last_batch_at: Option<u64>,
+    last_proposal_at: Option<u64>,
+}
+
+/// Seconds elapsed on L1 since the last batch and proposal, None if none was found in the scanned blocks
+#[derive(Debug, Clone)]
+pub struct OnchainStatus {
+    pub l1_block: u64,
+    pub since_last_batch: Option<u64>,
+    pub since_last_proposal: Option<u64>,
+    pub batch_threshold: u64,
+    pub proposal_threshold: u64,
+}
+
+pub trait TOnchainMonitorService: Send + Sync {
+    fn poll(&mut self) -> Result<OnchainStatus, Box<dyn std::error::Error>>;
+}
+
+// contracts that receive output proposals, depending on whether fault proofs are enabled
+const OUTPUT_SUBMISSION_CONTRACTS: [&str; 2] = ["L2OutputOracleProxy", "DisputeGameFactoryProxy"];
+
+// avoid scanning the whole chain on the first poll if thresholds are huge
+const MAX_LOOKBACK_BLOCKS: u64 = 1000;
+
+// implementations ===================================================
+
+impl OnchainMonitorService {
+    /// addresses is the content of the deployment addresses.json
+    pub fn new(
+        rpc: Box<dyn EthRpc>,
+        deployment: &Deployment,
+        addresses: &Value,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let network = &deployment.network_config;
+
+        let output_submission_addresses: Vec<String> = OUTPUT_SUBMISSION_CONTRACTS
+            .iter()
+            .filter_map(|contract| addresses[contract].as_str())
+            .map(|address| address.to_lowercase())
+            .collect();
+        if output_submission_addresses.is_empty() {
+            return Err("Neither L2OutputOracleProxy nor DisputeGameFactoryProxy found in addresses.json".into());
+        }
+
+        // a channel not submitted within channel_timeout l1 blocks is dropped, proposals are due every submission interval
+        let batch_threshold = network.channel_timeout as u64 * network.l1_block_time as u64;
+        let proposal_threshold = network.l2_output_oracle_submission_interval as u64 * network.l2_block_time as u64;
+
+        // look back twice the largest threshold so the first poll can tell late from missing
+        let lookback_blocks = (2 * batch_threshold.max(proposal_threshold) / (network.l1_block_time as u64).max(1))
+            .clamp(1, MAX_LOOKBACK_BLOCKS);
+
+        Ok(Self {
+            rpc,
+            l1_rpc_url: network.l1_rpc_url.clone(),
+            batch_inbox_address: network.batch_inbox_address.to_lowercase(),
+            output_submission_addresses,
+            batch_threshold,
+            proposal_threshold,
+            lookback_blocks,
+            last_scanned_block: None,
+            last_batch_at: None,
+            last_proposal_at: None,
+        })
+    }
+
+    fn request(&self, method: &str, params: Vec<Value>) -> Result<Value, Box<dyn std::error::Error>> {
+        let response = self
+            .rpc
+            .send_rpc_request(&self.l1_rpc_url, 1, method, params)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} failed: {}", method, error).into());
+        }
+
+        Ok(response["result"].clone())
+    }
+
+    fn block_number(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        parse_quantity(&self.request("eth_blockNumber", vec![])?)
+    }
+
+    fn scan_block(&mut self, number: u64) -> Result<u64, Box<dyn std::error::Error>> {
+        let block = self.request(
+            "eth_getBlockByNumber",
+            vec![json!(format!("0x{:x}", number)), json!(true)],
+        )?;
+        let timestamp = parse_quantity(&block["timestamp"])?;
+
+        for tx in block["transactions"].as_array().unwrap_or(&vec![]) {
+            let to = match tx["to"].as_str() {
+                Some(to) => to.to_lowercase(),
+                None => continue, // contract creation
+            };
+
+            if to == self.batch_inbox_address {
+                self.last_batch_at = Some(timestamp);
+            } else if self.output_submission_addresses.contains(&to) {
+                self.last_proposal_at = Some(timestamp);
+            }
+        }
+
+        Ok(timestamp)
+    }
+}
+
+impl TOnchainMonitorService for OnchainMonitorService {
+    fn poll(&mut self) -> Result<OnchainStatus, Box<dyn std::error::Error>> {
+        let latest = self.block_number()?;
+
+        // only scan blocks we haven't seen yet, bounded by the lookback window
+        let from = match self.last_scanned_block {
+            Some(last_scanned) => last_scanned + 1,
+            None => latest.saturating_sub(self.lookback_blocks - 1),
+        }
+        .max(latest.saturating_sub(MAX_LOOKBACK_BLOCKS - 1));
+
+        // elapsed times are measured against l1 time rather than the local clock
+        let mut now = None;
+        for number in from..=latest {
+            now = Some(self.scan_block(number)?);
+        }
+        let now = match now {
+            Some(now) => now,
+            None => parse_quantity(
+                &self.request(
+                    "eth_getBlockByNumber",
+                    vec![json!(format!("0x{:x}", latest)), json!(false)],
+                )?["timestamp"],
+            )?,
+        };
+        self.last_scanned_block = Some(latest);
+
+        Ok(OnchainStatus {
+            l1_block: latest,
+            since_last_batch: self.last_batch_at.map(|at| now.saturating_sub(at)),
+            since_last_proposal: self.last_proposal_at.map(|at| now.saturating_sub(at)),
+            batch_threshold: self.batch_threshold,
+            proposal_threshold: self.proposal_threshold,
+        })
+    }
+}
+
+impl OnchainStatus {
+    pub fn batches_delayed(&self) -> bool {
+        self.since_last_batch
+            .is_none_or(|since| since > self.batch_threshold)
+    }
+
+    pub fn proposals_delayed(&self) -> bool {
+        self.since_last_proposal
+            .is_none_or(|since| since > self.proposal_threshold)
+    }
+}
+
+fn parse_quantity(value: &Value) -> Result<u64, Box<dyn std::error::Error>> {
+    let hex = value
+        .as_str()
+        .ok_or("Expected hex quantity in rpc response")?;
+
+    Ok(u64::from_str_radix(hex.trim_start_matches("0x"), 16)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OnchainMonitorService, TOnchainMonitorService};
+    use crate::{
+        config::{AccountsConfig, NetworkConfig},
+        domain::Deployment,
+        infra::ethereum::MockEthRpc,
+    };
+    use serde_json::{json, Value};
+
+    const BATCH_INBOX: &str = "0xff00000000000000000000000000000000000042";
+    const L2_OUTPUT_ORACLE: &str = "0x1111111111111111111111111111111111111111";
+
+    fn deployment() -> Deployment {
+        let mut network_config = NetworkConfig::null();
+        network_config.batch_inbox_address = BATCH_INBOX.to_string();
+        network_config.l1_block_time = 12;
+        network_config.channel_timeout = 10; // 120s
+        network_config.l2_block_time = 2;
+        network_config.l2_output_oracle_submission_interval = 90; // 180s
+
+        Deployment::new(
+            "mock".to_string(),
+            "v0.0.1".to_string(),
+            "wakeuplabs".to_string(),
+            network_config,
+            AccountsConfig::null(),
+        )
+    }
+
+    /// chain with head at block 110 and blocks 12 seconds apart, holding the given (block, to) transactions
+    fn rpc(txs: Vec<(u64, &'static str)>) -> MockEthRpc {
+        let mut rpc = MockEthRpc::new();
+        rpc.expect_send_rpc_request()
+            .returning(move |_, _, method, params| match method {
+                "eth_blockNumber" => Ok(json!({ "result": "0x6e" })),
+                "eth_getBlockByNumber" => {
+                    let number = u64::from_str_radix(params[0].as_str().unwrap().trim_start_matches("0x"), 16)?;
+                    let transactions: Vec<Value> = txs
+                        .iter()
+                        .filter(|(block, _)| *block == number)
+                        .map(|(_, to)| json!({ "to": to }))
+                        .collect();
+
+                    Ok(json!({ "result": {
+                        "timestamp": format!("0x{:x}", number * 12),
+                        "transactions": transactions,
+                    }}))
+                }
+                _ => Err("unexpected method".into()),
+            });
+
+        rpc
+    }
+
+    #[test]
+    fn should_report_time_since_last_batch_and_proposal() {
+        let mut monitor = OnchainMonitorService::new(
+            Box::new(rpc(vec![
+                (105, BATCH_INBOX),
+                (108, BATCH_INBOX),
+                (104, L2_OUTPUT_ORACLE),
+            ])),
+            &deployment(),
+            &json!({ "L2OutputOracleProxy": L2_OUTPUT_ORACLE }),
+        )
+        .unwrap();
+
+        let status = monitor.poll().unwrap();
+
+        assert_eq!(status.l1_block, 110);
+        assert_eq!(status.since_last_batch, Some(24));
+        assert_eq!(status.since_last_proposal, Some(72));
+        assert!(!status.batches_delayed());
+        assert!(!status.proposals_delayed());
+    }
+
+    #[test]
+    fn should_flag_missing_submissions_as_delayed() {
+        let mut monitor = OnchainMonitorService::new(
+            Box::new(rpc(vec![(101, BATCH_INBOX)])),
+            &deployment(),
+            &json!({ "DisputeGameFactoryProxy": L2_OUTPUT_ORACLE }),
+        )
+        .unwrap();
+
+        let status = monitor.poll().unwrap();
+
+        assert_eq!(status.since_last_batch, Some(108));
+        assert!(!status.batches_delayed());
+        assert_eq!(status.since_last_proposal, None);
+        assert!(status.proposals_delayed());
+    }
+
+    #[test]
+    fn should_fail_without_output_submission_contracts() {
+        let result = OnchainMonitorService::new(Box::new(rpc(vec![])), &deployment(), &json!({}));
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::infra::ethereum::EthRpc;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    fs::File,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub struct ChainStatusService {
+    rpc: Box<dyn EthRpc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChainEndpoints {
+    pub l1_rpc: String,
+    pub l2_rpc: String,
+    pub node_rpc: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct L2BlockRef {
+    pub number: u64,
+    pub timestamp: u64,
+    pub l1_origin: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStatus {
+    pub l2_chain_id: u64,
+    pub l1_head: u64,
+    pub l2_head: u64,
+    pub unsafe_l2: L2BlockRef,
+    pub safe_l2: L2BlockRef,
+    pub finalized_l2: L2BlockRef,
+    /// l1 blocks between the l1 head and the l1 origin of the unsafe head
+    pub l1_origin_lag: u64,
+    pub sequencer_active: bool,
+}
+
+pub trait TChainStatusService: Send + Sync {
+    fn status(&self, endpoints: &ChainEndpoints) -> Result<ChainStatus, Box<dyn std::error::Error>>;
+}
+
+// as routed by the chart ingress to proxyd
+const INGRESS_RPC_PATH: &str = "/rpc";
+
+// without the admin api we consider the sequencer stopped after this many missed l2 blocks
+const MISSED_BLOCKS_TOLERANCE: u64 = 10;
+
+// implementations ===================================================
+
+impl ChainStatusService {
+    pub fn new(rpc: Box<dyn EthRpc>) -> Self {
+        Self { rpc }
+    }
+
+    fn request(&self, url: &str, method: &str, params: Vec<Value>) -> Result<Value, Box<dyn std::error::Error>> {
+        let response = self.rpc.send_rpc_request(url, 1, method, params)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} on {} failed: {}", method, url, error).into());
+        }
+
+        Ok(response["result"].clone())
+    }
+
+    fn block_number(&self, url: &str) -> Result<u64, Box<dyn std::error::Error>> {
+        let result = self.request(url, "eth_blockNumber", vec![])?;
+        let hex = result
+            .as_str()
+            .ok_or(format!("Unexpected eth_blockNumber response from {}", url))?;
+
+        Ok(u64::from_str_radix(hex.trim_start_matches("0x"), 16)?)
+    }
+
+    fn sequencer_active(&self, url: &str, unsafe_l2: &L2BlockRef, block_time: u64) -> bool {
+        // admin api may be disabled, fallback to checking the unsafe head keeps moving
+        if let Ok(Value::Bool(active)) = self.request(url, "admin_sequencerActive", vec![]) {
+            return active;
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        now.saturating_sub(unsafe_l2.timestamp) <= block_time * MISSED_BLOCKS_TOLERANCE
+    }
+}
+
+impl TChainStatusService for ChainStatusService {
+    fn status(&self, endpoints: &ChainEndpoints) -> Result<ChainStatus, Box<dyn std::error::Error>> {
+        let sync_status = self.request(&endpoints.node_rpc, "optimism_syncStatus", vec![])?;
+        let rollup_config = self.request(&endpoints.node_rpc, "optimism_rollupConfig", vec![])?;
+
+        let unsafe_l2 = L2BlockRef::from_json(&sync_status["unsafe_l2"])?;
+        let safe_l2 = L2BlockRef::from_json(&sync_status["safe_l2"])?;
+        let finalized_l2 = L2BlockRef::from_json(&sync_status["finalized_l2"])?;
+
+        let l1_head = self.block_number(&endpoints.l1_rpc)?;
+        let l2_head = self.block_number(&endpoints.l2_rpc)?;
+
+        let block_time = rollup_config["block_time"].as_u64().unwrap_or(2);
+        let sequencer_active = self.sequencer_active(&endpoints.node_rpc, &unsafe_l2, block_time);
+
+        Ok(ChainStatus {
+            l2_chain_id: rollup_config["l2_chain_id"].as_u64().unwrap_or_default(),
+            l1_head,
+            l2_head,
+            l1_origin_lag: l1_head.saturating_sub(unsafe_l2.l1_origin),
+            unsafe_l2,
+            safe_l2,
+            finalized_l2,
+            sequencer_active,
+        })
+    }
+}
+
+impl ChainEndpoints {
+    /// Public l2 rpc of a deployment, behind the chart ingress. Uses the domain configured in the helm values
+    /// and falls back to the load balancer from the infra artifacts when there's none.
+    pub fn l2_rpc_from_infra(infra_artifacts: &Path, helm_values: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        let values: serde_yaml::Value = serde_yaml::from_reader(File::open(helm_values)?)?;
+        if let Some(host) = values["domain"]["host"].as_str() {
+            if host != "localhost" {
+                return Ok(format!("https://{}{}", host, INGRESS_RPC_PATH));
+            }
+        }
+
+        let outputs: Value = serde_json::from_reader(File::open(infra_artifacts)?)?;
+        let elb_dnsname = outputs["elb_dnsname"]["value"]
+            .as_str()
+            .ok_or("elb_dnsname not found in infra artifacts")?;
+
+        Ok(format!("http://{}{}", elb_dnsname, INGRESS_RPC_PATH))
+    }
+}
+
+impl L2BlockRef {
+    fn from_json(value: &Value) -> Result<Self, Box<dyn std::error::Error>> {
+        let field = |value: &Value, name: &str| {
+            value[name].as_u64().ok_or(format!(
+                "Unexpected optimism_syncStatus response, missing {}",
+                name
+            ))
+        };
+
+        Ok(Self {
+            number: field(value, "number")?,
+            timestamp: field(value, "timestamp")?,
+            l1_origin: field(&value["l1origin"], "number")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChainEndpoints, ChainStatusService, TChainStatusService};
+    use crate::infra::ethereum::MockEthRpc;
+    use serde_json::{json, Value};
+
+    fn endpoints() -> ChainEndpoints {
+        ChainEndpoints {
+            l1_rpc: "l1".to_string(),
+            l2_rpc: "l2".to_string(),
+            node_rpc: "node".to_string(),
+        }
+    }
+
+    fn block(number: u64, l1_origin: u64) -> Value {
+        json!({ "number": number, "timestamp": 0, "l1origin": { "number": l1_origin } })
+    }
+
+    fn rpc(sequencer_active: Value) -> MockEthRpc {
+        let mut rpc = MockEthRpc::new();
+        rpc.expect_send_rpc_request()
+            .returning(move |url, _, method, _| match (url, method) {
+                ("node", "optimism_syncStatus") => Ok(json!({ "result": {
+                    "unsafe_l2": block(120, 95),
+                    "safe_l2": block(100, 90),
+                    "finalized_l2": block(80, 85),
+                }})),
+                ("node", "optimism_rollupConfig") => Ok(json!({ "result": { "block_time": 2, "l2_chain_id": 42069 } })),
+                ("node", "admin_sequencerActive") => Ok(sequencer_active.clone()),
+                ("l1", "eth_blockNumber") => Ok(json!({ "result": "0x64" })),
+                ("l2", "eth_blockNumber") => Ok(json!({ "result": "0x78" })),
+                _ => Err("unexpected request".into()),
+            });
+
+        rpc
+    }
+
+    #[test]
+    fn should_report_heads_and_l1_origin_lag() {
+        let service = ChainStatusService::new(Box::new(rpc(json!({ "result": true }))));
+
+        let status = service.status(&endpoints()).unwrap();
+
+        assert_eq!(status.l2_chain_id, 42069);
+        assert_eq!(status.l1_head, 100);
+        assert_eq!(status.l2_head, 120);
+        assert_eq!(status.unsafe_l2.number, 120);
+        assert_eq!(status.safe_l2.number, 100);
+        assert_eq!(status.finalized_l2.number, 80);
+        assert_eq!(status.l1_origin_lag, 5);
+        assert!(status.sequencer_active);
+    }
+
+    #[test]
+    fn should_fallback_to_head_progress_without_admin_api() {
+        let service = ChainStatusService::new(Box::new(rpc(
+            json!({ "error": { "code": -32601, "message": "the method admin_sequencerActive does not exist" } }),
+        )));
+
+        let status = service.status(&endpoints()).unwrap();
+
+        // unsafe head timestamp is way in the past
+        assert!(!status.sequencer_active);
+    }
+
+    #[test]
+    fn should_prefer_configured_domain_for_l2_rpc() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let infra_artifacts = dir.path().join("infra_artifacts.json");
+        let values = dir.path().join("values.yaml");
+        std::fs::write(
+            &infra_artifacts,
+            r#"{"elb_dnsname": {"value": "abc.elb.amazonaws.com"}}"#,
+        )
+        .unwrap();
+
+        std::fs::write(&values, "domain:\n  host: localhost\n").unwrap();
+        assert_eq!(
+            ChainEndpoints::l2_rpc_from_infra(&infra_artifacts, &values).unwrap(),
+            "http://abc.elb.amazonaws.com/rpc"
+        );
+
+        std::fs::write(&values, "domain:\n  host: rollup.example.com\n").unwrap();
+        assert_eq!(
+            ChainEndpoints::l2_rpc_from_infra(&infra_artifacts, &values).unwrap(),
+            "https://rollup.example.com/rpc"
+        );
+    }
+}
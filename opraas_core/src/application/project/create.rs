@@ -58,6 +58,7 @@ impl TCreateProjectService for CreateProjectService {
         self.stack_infra_repository.pull(&Stack::new(
             project.infra.helm.clone(),
             project.infra.aws.clone(),
+            project.releases.clone(),
             None,
         ))?;
 
@@ -97,6 +98,8 @@ Recommended PC specifications to run it all smoothly:
 - 8 GB RAM
 - + 25 GB storage available on top of installed programs
 
+Run `npx opruaas doctor` to check all of the above at once. It verifies the tools and their versions, that the docker daemon and your current kubernetes context are reachable, the chart dependency helm repos, AWS credentials, available RAM and disk, and that the `.env` defines every variable the config reads, counting private keys kept in `./keystore` as set. Each check reports pass, warn or fail with a hint on how to fix it, and the command exits with an error if any check fails. Add `--json` for machine readable output.
+
 ### Commands
 
 Usage: `opruaas [OPTIONS] <COMMAND>`  
@@ -107,8 +110,17 @@ Usage: `opruaas [OPTIONS] <COMMAND>`
 - `build`    Compile sources and create Docker images  
 - `release`  Tag and push the already built Docker images to the registry for deployment  
 - `dev`      Spin up a local development environment  
+- `config`   Manage the project config. Subcommand must be one of: `validate`  
+- `doctor`   Diagnose your environment before running the heavier commands  
 - `deploy`   Deploy your blockchain. Target must be one of: `contracts`, `infra`, `all`  
+- `deployments` Manage the project deployments. Subcommand must be one of: `list`, `export`, `import`  
+- `keys`     Manage the project accounts. Subcommand must be one of: `generate`  
 - `inspect`  Get details about the current deployment. Target must be one of: `contracts`, `infra`  
+- `status`   Check the sync status of a deployment  
+- `logs`     Stream the logs of a component's pods, or of every component with `--all`  
+- `monitor`  Monitor your chain. Target must be one of: `onchain`  
+- `upgrade`  Roll a deployment to a new release, optionally only some of its components  
+- `destroy`  Tear down a deployment's infra and archive its records  
 - `help`     Print this message or the help for the given subcommand(s)  
 
 #### Options:
@@ -116,6 +128,9 @@ Usage: `opruaas [OPTIONS] <COMMAND>`
 - `-h`, `--help`     Print help  
 - `-V`, `--version`  Print version
 
+#### Non-interactive usage:
+`release`, `deploy` and `dev` prompt for the registry and release name and ask for confirmation before acting. To run them unattended (e.g. in CI) pass `--registry`, `--release` and `--yes`, or set `OPRUAAS_REGISTRY`, `OPRUAAS_RELEASE` and `OPRUAAS_YES=true`. `release` only tags your git repository when `--git-tag` (or `OPRUAAS_GIT_TAG=true`) is given. When stdin is not a terminal, commands fail listing the values they're missing instead of waiting for input.
+
 ### Create a New Project and Build Releases from Source  
 
 Follow these steps to create a new project and build releases:  
@@ -137,6 +152,46 @@ npx opruaas build contracts
 npx opruaas release contracts
 ```
 
+Build skips artifacts whose local image was built from the same source commit and uncommitted changes, Dockerfile and platforms, pass `--force` to rebuild them anyway.
+
+To run on nodes of a different architecture than your machine, e.g. amd64 EKS nodes from an Apple Silicon laptop, pass the same `--platform` list to build and release. Images are built with docker buildx and the release pushes a multi-platform manifest list. The node, batcher and proposer are cross-compiled, while geth, the contracts and the explorer are built under emulation, so the buildx builder needs QEMU for the other platforms:
+
+```bash
+npx opruaas build all --platform linux/amd64,linux/arm64
+npx opruaas release all --platform linux/amd64,linux/arm64
+```
+
+Every release writes `releases/<name>.json`, a manifest with the pushed digest, source repository, resolved commit and build time of each artifact. Commit it along with the project: deploy, upgrade and dev pin the images of a release that has a manifest by `repository@sha256:...`, so pushing the same tag again doesn't change what runs.
+
+To build once and move the same images from staging to production, promote a release instead of releasing it again. Every artifact of the release is pulled, retagged and pushed, or copied registry to registry for multi-platform releases, and the command fails unless the pushed digests match the original ones. The manifest of the source release decides which artifacts are copied, and the promoted images are recorded in the manifest of the target release:
+
+```bash
+npx opruaas release promote --from localhost:5000/v0.1.0 --to docker.io/wakeuplabs/v0.1.0
+```
+
+To see what's already in a registry, list its releases. Releases that don't have an image for every artifact are marked with the ones they're missing (the explorer is optional), and when dev or deploy ask for a release name they offer these releases to pick from:
+
+```bash
+npx opruaas release list --registry docker.io/wakeuplabs
+```
+
+Each artifact in `config.toml` is cloned from `source_repo` at `source_ref`, a branch, tag or full commit SHA. `source_repo` is a GitHub `owner/repo` or any URL git can clone, so forks of the optimism monorepo work too. Private repositories authenticate like git does: ssh URLs through your ssh agent and https ones through your git credential helper. To build from a checkout you already have, set `source_path` instead and init links it into `src`:
+
+```toml
+[artifacts.node]
+source_repo = "acme/optimism"
+source_ref = "acme/custom-derivation"
+
+[artifacts.batcher]
+source_path = "../optimism"
+```
+
+Init records the exact commit each source resolved to in `src/<artifact>.source.json`. Only the commit you ask for is downloaded, and artifacts cloned from the same repository are fetched through a single object store in `src/.objects`, so `init all` fetches the optimism monorepo once. Each checkout then keeps its own copy of its commit and works on its own, inside docker builds too.
+
+Init skips sources that are already there. After bumping `source_ref`, run `init <target> --update` to fetch the new ref and check it out in place; it prints the old and new commit of each artifact. Sources with uncommitted changes are left alone unless you pass `--force`, which discards those changes. Linked `source_path` checkouts are never moved.
+
+The Blockscout explorer is an artifact too, `explorer` in `init`, `build`, `release` and `upgrade --only`, built from `blockscout/blockscout` with `opraas_core/docker/explorer.dockerfile` unless `[artifacts.explorer]` says otherwise. Point it at a fork to customise or brand it; deploy, upgrade and dev run the `op-explorer` image of the release instead of the upstream one. Releases without it, like those made before the explorer was an artifact, keep running the upstream Blockscout.
+
 ### Test releases with dev
 
 The dev command simplifies the setup for local testing. It performs the following actions:
@@ -162,6 +217,37 @@ Once all deployments are up and running, it may take some time for the system to
 - RPC responsiveness: The RPC endpoint may initially take a few moments to respond to queries.
 - Explorer indexing: The block explorer will need time to finish indexing before it can display your transactions.
 
+**Running local images**
+
+To try changes to the artifacts sources without releasing them, build them and pass `--local-images`. Dev pushes the images built with `build` to a throwaway registry at `localhost:5000`, which is removed on teardown, and runs the contracts deployment and the infra from them:
+
+```bash
+npx opruaas build all
+npx opruaas dev --local-images
+```
+
+**Running on Anvil**
+
+The L1 runs on geth by default, which only supports chain id 1337. Pass `--l1 anvil` to run it on [Anvil](https://book.getfoundry.sh/anvil/) instead, which keeps the `l1_chain_id` from your config. Use `--l1-state` to persist the L1 across sessions, the state is loaded from the file when it exists and dumped back to it on stop:
+
+```bash
+npx opruaas dev --default --l1 anvil --l1-state ./.opruaas/anvil-state.json
+```
+
+Anvil mines a block every second and pre-funds 30 accounts with 10000 ether each. Change that with `--block-time`, `--accounts` and `--balance`.
+
+**Running in the background**
+
+By default dev stays in the foreground and tears everything down on Ctrl + C. Pass `--detach` to leave the devnet running instead, its L1 container, Helm release, namespace and ports are recorded in `./.opruaas/devnet.json`:
+
+```bash
+npx opruaas dev --default --detach
+npx opruaas dev status   # L1 node and pods health
+npx opruaas dev stop     # same teardown as Ctrl + C
+```
+
+`dev stop` also cleans up after a dev session that was killed before it could tear down.
+
 **Testing Your Setup**
 
 If you have cast installed, the following commands can help you test the deployment and interact with the setup:
@@ -220,6 +306,24 @@ Once the setup is complete, you can access the following services:
 - Off-chain Monitoring: http://localhost:80/monitoring
 - Explorer: http://localhost:80
 
+### Generate accounts
+
+```bash
+npx opruaas keys generate
+```
+
+Creates a new key pair for each of the admin, batcher, sequencer, proposer, deployer and challenger roles, writes the private keys and addresses to `.env` and updates the addresses in `config.toml`. Pass `--mnemonic "<phrase>"` (or `OPRUAAS_MNEMONIC`) to derive them from a BIP-39 mnemonic instead, at `m/44'/60'/0'/0/<index>` unless `--derivation-path` says otherwise, or `--mnemonic` alone to generate a new mnemonic. With `--encrypt` the private keys are written as Web3 Secret Storage keystores to `./keystore` instead of `.env`, protected by a password you're prompted for (or `OPRUAAS_KEYSTORE_PASSWORD`). Commands that need the keys, like `deploy`, `upgrade` and `destroy`, unlock the keystores with the same password, and `keystore/` is kept out of git.
+
+Only the private keys are required. Addresses left out of `config.toml` and `.env` are derived from them, and when both are given they must match, otherwise `deploy` and `config validate` fail listing every mismatched role. `deploy` and `config validate` also warn when several roles share a private key, which is fine for dev but unsafe anywhere else.
+
+### Validate the config
+
+```bash
+npx opruaas config validate
+```
+
+Checks `config.toml` beyond its syntax and lists every problem found at once: L1 and L2 chain ids must differ, `fault_game_split_depth` must be lower than `fault_game_max_depth`, `max_sequencer_drift` must fit in the sequencer window, `l2_block_time` can't exceed `l1_block_time`, addresses must be valid and EIP-55 checksummed and numeric strings must parse. `deploy` and `dev` run the same validation before doing anything.
+
 ### Deploy contracts/infra/all
 
 Ensure that your `config.toml` configuration file is properly set up before proceeding.
@@ -232,6 +336,8 @@ npx opruaas -v deploy all --name my-prod-deployment
   Add `--deploy-deployer` if the L1 chain does not already have a deployer. For most popular L1 chains, this step is unnecessary.
 
 
+Before deploying anything, deploy checks the registry has an image for every release it's going to use, the contracts one and those of the node, geth, batcher and proposer, by digest when the release has a manifest. The explorer image is checked too when the release manifest recorded one and `explorer.enabled` is set in the chart values. Private registries are queried with the credentials of `docker login`, credential helpers included. If any is missing it stops listing them all, so a partial release doesn't cost L1 gas for contracts that can't be run. `deploy infra` checks the releases recorded for the existing deployment.
+
 The deployment process will create a deployments/my-prod-deployment directory containing the generated artifacts.
 
 - Artifacts:
@@ -239,10 +345,72 @@ The deployment process will create a deployments/my-prod-deployment directory co
 - Inspecting Artifacts:
   You can manually review the artifacts or use the inspect command for easier analysis.
 
+### List deployments
+
+```bash
+npx opruaas deployments list
+```
+
+Shows every deployment in `./deployments` with its release, registry, L1/L2 chain ids and whether contracts and infra artifacts are present. Add `--json` for machine readable output.
+
+### Share a deployment
+
+```bash
+npx opruaas deployments export my-prod-deployment --encrypt
+npx opruaas deployments import my-prod-deployment.opruaas
+```
+
+`export` packs a deployment's config and artifacts into a single versioned `.opruaas` bundle with a manifest holding every file's SHA-256, `--encrypt` protects it with a passphrase you're prompted for (or `--passphrase`/`OPRUAAS_BUNDLE_PASSPHRASE`). `import` verifies the checksums before restoring it into `./deployments` and refuses to replace an existing deployment unless `--force` is given, in which case the current one is archived. Private keys are never part of a bundle.
+
+### Check a deployment status
+
+```bash
+kubectl port-forward service/op-node-sequencer-service 7545:7545 --namespace <namespace>
+npx opruaas status --name my-prod-deployment --node-rpc http://localhost:7545
+```
+
+Prints the unsafe, safe and finalized L2 heads, how far the L1 origin lags behind the L1 head, the op-geth head and whether the sequencer is active. The L2 rpc is taken from the infra artifacts (your configured domain or the load balancer) and the L1 rpc from the deployment config, override them with `--l2-rpc` and `--l1-rpc`. Add `--json` for machine readable output.
+
+### Stream logs
+
+```bash
+npx opruaas logs batcher --follow
+npx opruaas logs node --name my-prod-deployment --since 10m
+npx opruaas logs --all -f
+```
+
+Streams the logs of every pod of a component (`node`, `geth`, `batcher`, `proposer` or `explorer`) with one colored prefix per pod, `--all` interleaves every component. Defaults to the `dev` devnet in your current kubernetes context; for other deployments the cluster is taken from the infra artifacts. Add `--since` to only get logs newer than a relative duration.
+
+### Monitor a deployment
+
+```bash
+npx opruaas monitor onchain --name my-prod-deployment
+```
+
+Polls L1 every 30 seconds (change it with `--interval`) for batcher transactions to the `batch_inbox_address` and output proposals to the L2OutputOracle or DisputeGameFactory, and reports how long ago the last of each landed. It warns when batches are older than `channel_timeout` L1 blocks or proposals are older than `l2_output_oracle_submission_interval` L2 blocks.
+
+### Upgrade a deployment
+
+```bash
+npx opruaas upgrade --name my-prod-deployment --release v0.0.2
+```
+
+This re-renders the chart values for the new release, applies them to the chain helm release through terraform, so the next `terraform apply` keeps them, and waits for every component to roll out before saving the release to `deployments/my-prod-deployment/config/release.json`. Use `--only node,geth` to upgrade just some of the components, the rest keep running their current release, and `--yes` (or `OPRUAAS_YES=true`) to skip the confirmation when running unattended. Besides kubectl it needs terraform and the aws CLI, which it uses to reach the cluster.
+
+### Destroy a deployment
+
+```bash
+npx opruaas destroy --name my-prod-deployment
+```
+
+This uninstalls the chain helm release, runs `terraform destroy` on the AWS resources and moves `deployments/my-prod-deployment` to `deployments/.archive`. The terraform state in `infra/aws` is shared by the whole project, so `destroy` refuses to run unless its cluster is the one recorded for the deployment. Add `--keep-records` to leave the deployment records in place, and `--yes` (or `OPRUAAS_YES=true`) to skip the confirmation when running unattended.
+
 "#;
 
 const GITIGNORE: &str = r#"
 .env
+.opruaas/
+keystore/
 "#;
 
 const ENV_FILE: &str = r#"
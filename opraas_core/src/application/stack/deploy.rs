@@ -1,4 +1,4 @@
-use crate::domain::{self, Deployment, Stack};
+use crate::domain::{self, ArtifactKind, Deployment, Stack};
 use serde_json::Value;
 use std::io::Read;
 use std::{collections::HashMap, io::Cursor};
@@ -14,6 +14,13 @@ pub struct StackInfraInspectorService {}
 
 pub trait TStackInfraDeployerService: Send + Sync {
     fn deploy(&self, stack: &Stack) -> Result<Deployment, Box<dyn std::error::Error>>;
+    fn destroy(&self, stack: &Stack, keep_records: bool) -> Result<(), Box<dyn std::error::Error>>;
+    fn upgrade(
+        &self,
+        stack: &Stack,
+        release_name: &str,
+        artifacts: &[ArtifactKind],
+    ) -> Result<Deployment, Box<dyn std::error::Error>>;
     fn find(&self, name: &str) -> Result<Option<Deployment>, Box<dyn std::error::Error>>;
 }
 
@@ -52,6 +59,74 @@ impl TStackInfraDeployerService for StackInfraDeployerService {
         Ok(deployment)
     }
 
+    fn destroy(&self, stack: &Stack, keep_records: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let deployment = match &stack.deployment {
+            Some(deployment) => deployment,
+            None => return Err("Stack does not contain deployment".into()),
+        };
+
+        self.stack_infra_repository.pull(stack)?;
+
+        self.stack_deployer.destroy(stack)?;
+
+        if !keep_records {
+            self.deployment_repository.archive(&deployment.name)?;
+        }
+
+        Ok(())
+    }
+
+    fn upgrade(
+        &self,
+        stack: &Stack,
+        release_name: &str,
+        artifacts: &[ArtifactKind],
+    ) -> Result<Deployment, Box<dyn std::error::Error>> {
+        let mut deployment = match &stack.deployment {
+            Some(deployment) => deployment.clone(),
+            None => return Err("Stack does not contain deployment".into()),
+        };
+
+        // an empty list means the whole release moves forward, otherwise only the given artifacts do
+        let artifacts = match artifacts.is_empty() {
+            true => {
+                deployment.release_name = release_name.to_string();
+                deployment.release_overrides.clear();
+                vec![
+                    ArtifactKind::Node,
+                    ArtifactKind::Geth,
+                    ArtifactKind::Batcher,
+                    ArtifactKind::Proposer,
+                    ArtifactKind::Explorer,
+                ]
+            }
+            false => {
+                for artifact in artifacts {
+                    deployment.release_overrides.insert(
+                        artifact.artifact_name().to_string(),
+                        release_name.to_string(),
+                    );
+                }
+                artifacts.to_vec()
+            }
+        };
+
+        let upgraded = Stack::new(
+            stack.helm.clone(),
+            stack.aws.clone(),
+            stack.releases.clone(),
+            Some(deployment),
+        );
+
+        self.stack_infra_repository.pull(&upgraded)?;
+        self.stack_deployer.upgrade(&upgraded, &artifacts)?;
+
+        let mut deployment = upgraded.deployment.unwrap();
+        self.deployment_repository.save(&mut deployment)?;
+
+        Ok(deployment)
+    }
+
     fn find(&self, name: &str) -> Result<Option<Deployment>, Box<dyn std::error::Error>> {
         self.deployment_repository.find(name)
     }
@@ -102,3 +177,186 @@ impl TStackInfraInspectorService for StackInfraInspectorService {
         Err("Required deployment files not found in the ZIP".into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{StackInfraDeployerService, TStackInfraDeployerService};
+    use crate::{
+        config::{AccountsConfig, NetworkConfig},
+        domain::{
+            ArtifactKind, Deployment, MockTDeploymentRepository, MockTStackInfraDeployer, MockTStackInfraRepository,
+            Stack,
+        },
+    };
+    use std::path::PathBuf;
+
+    fn stack() -> Stack {
+        Stack::new(
+            PathBuf::new(),
+            PathBuf::new(),
+            PathBuf::new(),
+            Some(Deployment::new(
+                "mock".to_string(),
+                "v0.0.1".to_string(),
+                "wakeuplabs".to_string(),
+                NetworkConfig::null(),
+                AccountsConfig::null(),
+            )),
+        )
+    }
+
+    #[test]
+    fn should_archive_records_after_destroy() {
+        let mut mock_deployer = MockTStackInfraDeployer::new();
+        let mut mock_infra_repo = MockTStackInfraRepository::new();
+        let mut mock_deployment_repo = MockTDeploymentRepository::new();
+
+        mock_infra_repo.expect_pull().returning(|_| Ok(()));
+        mock_deployer
+            .expect_destroy()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_deployment_repo
+            .expect_archive()
+            .withf(|name| name == "mock")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = StackInfraDeployerService {
+            stack_deployer: Box::new(mock_deployer),
+            stack_infra_repository: Box::new(mock_infra_repo),
+            deployment_repository: Box::new(mock_deployment_repo),
+        };
+
+        let result = service.destroy(&stack(), false);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_keep_records_if_requested() {
+        let mut mock_deployer = MockTStackInfraDeployer::new();
+        let mut mock_infra_repo = MockTStackInfraRepository::new();
+        let mut mock_deployment_repo = MockTDeploymentRepository::new();
+
+        mock_infra_repo.expect_pull().returning(|_| Ok(()));
+        mock_deployer
+            .expect_destroy()
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_deployment_repo.expect_archive().never();
+
+        let service = StackInfraDeployerService {
+            stack_deployer: Box::new(mock_deployer),
+            stack_infra_repository: Box::new(mock_infra_repo),
+            deployment_repository: Box::new(mock_deployment_repo),
+        };
+
+        let result = service.destroy(&stack(), true);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn should_not_archive_records_if_destroy_fails() {
+        let mut mock_deployer = MockTStackInfraDeployer::new();
+        let mut mock_infra_repo = MockTStackInfraRepository::new();
+        let mut mock_deployment_repo = MockTDeploymentRepository::new();
+
+        mock_infra_repo.expect_pull().returning(|_| Ok(()));
+        mock_deployer
+            .expect_destroy()
+            .returning(|_| Err("terraform failed".into()));
+        mock_deployment_repo.expect_archive().never();
+
+        let service = StackInfraDeployerService {
+            stack_deployer: Box::new(mock_deployer),
+            stack_infra_repository: Box::new(mock_infra_repo),
+            deployment_repository: Box::new(mock_deployment_repo),
+        };
+
+        let result = service.destroy(&stack(), false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn should_upgrade_whole_release() {
+        let mut mock_deployer = MockTStackInfraDeployer::new();
+        let mut mock_infra_repo = MockTStackInfraRepository::new();
+        let mut mock_deployment_repo = MockTDeploymentRepository::new();
+
+        mock_infra_repo.expect_pull().returning(|_| Ok(()));
+        mock_deployer
+            .expect_upgrade()
+            .withf(|stack, artifacts| {
+                stack.deployment.as_ref().unwrap().release_name == "v0.0.2" && artifacts.len() == 5
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_deployment_repo
+            .expect_save()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = StackInfraDeployerService {
+            stack_deployer: Box::new(mock_deployer),
+            stack_infra_repository: Box::new(mock_infra_repo),
+            deployment_repository: Box::new(mock_deployment_repo),
+        };
+
+        let deployment = service.upgrade(&stack(), "v0.0.2", &[]).unwrap();
+        assert_eq!(deployment.release_name, "v0.0.2");
+        assert_eq!(deployment.release_for("op-geth"), "v0.0.2");
+    }
+
+    #[test]
+    fn should_upgrade_only_requested_artifacts() {
+        let mut mock_deployer = MockTStackInfraDeployer::new();
+        let mut mock_infra_repo = MockTStackInfraRepository::new();
+        let mut mock_deployment_repo = MockTDeploymentRepository::new();
+
+        mock_infra_repo.expect_pull().returning(|_| Ok(()));
+        mock_deployer
+            .expect_upgrade()
+            .withf(|_, artifacts| artifacts == [ArtifactKind::Node])
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_deployment_repo
+            .expect_save()
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let service = StackInfraDeployerService {
+            stack_deployer: Box::new(mock_deployer),
+            stack_infra_repository: Box::new(mock_infra_repo),
+            deployment_repository: Box::new(mock_deployment_repo),
+        };
+
+        let deployment = service
+            .upgrade(&stack(), "v0.0.2", &[ArtifactKind::Node])
+            .unwrap();
+        assert_eq!(deployment.release_name, "v0.0.1");
+        assert_eq!(deployment.release_for("op-node"), "v0.0.2");
+        assert_eq!(deployment.release_for("op-geth"), "v0.0.1");
+    }
+
+    #[test]
+    fn should_not_save_release_if_upgrade_fails() {
+        let mut mock_deployer = MockTStackInfraDeployer::new();
+        let mut mock_infra_repo = MockTStackInfraRepository::new();
+        let mut mock_deployment_repo = MockTDeploymentRepository::new();
+
+        mock_infra_repo.expect_pull().returning(|_| Ok(()));
+        mock_deployer
+            .expect_upgrade()
+            .returning(|_, _| Err("helm failed".into()));
+        mock_deployment_repo.expect_save().never();
+
+        let service = StackInfraDeployerService {
+            stack_deployer: Box::new(mock_deployer),
+            stack_infra_repository: Box::new(mock_infra_repo),
+            deployment_repository: Box::new(mock_deployment_repo),
+        };
+
+        let result = service.upgrade(&stack(), "v0.0.2", &[]);
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,6 @@
+use crate::infra::ethereum::Wallet;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::Path};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct AccountsConfig {
@@ -31,47 +33,199 @@ pub struct AccountsConfig {
 mod defaults {
     use std::env;
 
-    // accounts
+    // accounts, addresses are derived from the private keys when missing. private keys kept in keystores are
+    // missing until AccountsConfig::unlock_keystores exports them
     pub fn admin_address() -> String {
-        env::var("ADMIN_ADDRESS").expect("ADMIN_ADDRESS must be set")
+        env::var("ADMIN_ADDRESS").unwrap_or_default()
     }
     pub fn admin_private_key() -> String {
-        env::var("ADMIN_PRIVATE_KEY").expect("ADMIN_PRIVATE_KEY must be set")
+        env::var("ADMIN_PRIVATE_KEY").unwrap_or_default()
     }
     pub fn batcher_address() -> String {
-        env::var("BATCHER_ADDRESS").expect("BATCHER_ADDRESS must be set")
+        env::var("BATCHER_ADDRESS").unwrap_or_default()
     }
     pub fn batcher_private_key() -> String {
-        env::var("BATCHER_PRIVATE_KEY").expect("BATCHER_PRIVATE_KEY must be set")
+        env::var("BATCHER_PRIVATE_KEY").unwrap_or_default()
     }
     pub fn proposer_address() -> String {
-        env::var("PROPOSER_ADDRESS").expect("PROPOSER_ADDRESS must be set")
+        env::var("PROPOSER_ADDRESS").unwrap_or_default()
     }
     pub fn proposer_private_key() -> String {
-        env::var("PROPOSER_PRIVATE_KEY").expect("PROPOSER_PRIVATE_KEY must be set")
+        env::var("PROPOSER_PRIVATE_KEY").unwrap_or_default()
     }
     pub fn sequencer_address() -> String {
-        env::var("SEQUENCER_ADDRESS").expect("SEQUENCER_ADDRESS must be set")
+        env::var("SEQUENCER_ADDRESS").unwrap_or_default()
     }
     pub fn sequencer_private_key() -> String {
-        env::var("SEQUENCER_PRIVATE_KEY").expect("SEQUENCER_PRIVATE_KEY must be set")
+        env::var("SEQUENCER_PRIVATE_KEY").unwrap_or_default()
     }
     pub fn deployer_address() -> String {
-        env::var("DEPLOYER_ADDRESS").expect("DEPLOYER_ADDRESS must be set")
+        env::var("DEPLOYER_ADDRESS").unwrap_or_default()
     }
     pub fn deployer_private_key() -> String {
-        env::var("DEPLOYER_PRIVATE_KEY").expect("DEPLOYER_PRIVATE_KEY must be set")
+        env::var("DEPLOYER_PRIVATE_KEY").unwrap_or_default()
     }
 
     pub fn challenger_address() -> String {
-        env::var("CHALLENGER_ADDRESS").expect("CHALLENGER_ADDRESS must be set")
+        env::var("CHALLENGER_ADDRESS").unwrap_or_default()
     }
     pub fn challenger_private_key() -> String {
-        env::var("CHALLENGER_PRIVATE_KEY").expect("CHALLENGER_PRIVATE_KEY must be set")
+        env::var("CHALLENGER_PRIVATE_KEY").unwrap_or_default()
     }
 }
 
 impl AccountsConfig {
+    /// roles with an account, each one has `{role}_address` and `{role}_private_key` fields
+    pub const ROLES: [&'static str; 6] = [
+        "admin",
+        "batcher",
+        "sequencer",
+        "proposer",
+        "deployer",
+        "challenger",
+    ];
+
+    /// env variables the config can't be loaded without
+    pub const ENV_VARS: [&'static str; 6] = [
+        "ADMIN_PRIVATE_KEY",
+        "BATCHER_PRIVATE_KEY",
+        "SEQUENCER_PRIVATE_KEY",
+        "PROPOSER_PRIVATE_KEY",
+        "DEPLOYER_PRIVATE_KEY",
+        "CHALLENGER_PRIVATE_KEY",
+    ];
+
+    /// Derives missing addresses from their private keys. Fails listing every address that doesn't match its key.
+    pub fn resolve(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut problems = vec![];
+
+        for (role, address, private_key) in self.accounts_mut() {
+            if private_key.is_empty() {
+                problems.push(format!(
+                    "{}_PRIVATE_KEY is not set, add it to .env or keep it in ./keystore",
+                    role.to_uppercase()
+                ));
+                continue;
+            }
+
+            let wallet = match Wallet::from_private_key(private_key) {
+                Ok(wallet) => wallet,
+                Err(e) => {
+                    problems.push(format!("{}_PRIVATE_KEY: {}", role.to_uppercase(), e));
+                    continue;
+                }
+            };
+
+            if address.is_empty() {
+                *address = wallet.address;
+            } else if !address.eq_ignore_ascii_case(&wallet.address) {
+                problems.push(format!(
+                    "{}_address is {} but {}_PRIVATE_KEY belongs to {}",
+                    role,
+                    address,
+                    role.to_uppercase(),
+                    wallet.address
+                ));
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(format!(
+                "Accounts don't match their private keys:\n  - {}",
+                problems.join("\n  - ")
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Roles without a private key in the env that have a keystore in `dir`
+    pub fn locked_roles(dir: &Path) -> Vec<&'static str> {
+        Self::ROLES
+            .iter()
+            .zip(Self::ENV_VARS)
+            .filter(|(role, var)| std::env::var(var).is_err() && dir.join(format!("{}.json", role)).exists())
+            .map(|(role, _)| *role)
+            .collect()
+    }
+
+    /// Decrypts the keystores of the locked roles and exports their private keys to the env, where the config and
+    /// deployments read them from
+    pub fn unlock_keystores(dir: &Path, password: &str) -> Result<(), Box<dyn std::error::Error>> {
+        for role in Self::locked_roles(dir) {
+            let wallet = Wallet::decrypt(&dir.join(format!("{}.json", role)), password)?;
+            std::env::set_var(
+                format!("{}_PRIVATE_KEY", role.to_uppercase()),
+                wallet.private_key,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Groups of roles sharing the same private key, fine for dev but unsafe anywhere else
+    pub fn reused_keys(&self) -> Vec<Vec<&'static str>> {
+        let mut roles_by_key: HashMap<String, Vec<&'static str>> = HashMap::new();
+        for (role, private_key) in Self::ROLES.iter().zip(self.private_keys()) {
+            roles_by_key
+                .entry(private_key.trim().trim_start_matches("0x").to_lowercase())
+                .or_default()
+                .push(role);
+        }
+
+        let mut reused: Vec<Vec<&'static str>> = roles_by_key
+            .into_values()
+            .filter(|roles| roles.len() > 1)
+            .collect();
+        reused.sort();
+
+        reused
+    }
+
+    // in the same order as ROLES
+    fn private_keys(&self) -> [&String; 6] {
+        [
+            &self.admin_private_key,
+            &self.batcher_private_key,
+            &self.sequencer_private_key,
+            &self.proposer_private_key,
+            &self.deployer_private_key,
+            &self.challenger_private_key,
+        ]
+    }
+
+    fn accounts_mut(&mut self) -> [(&'static str, &mut String, &String); 6] {
+        [
+            ("admin", &mut self.admin_address, &self.admin_private_key),
+            (
+                "batcher",
+                &mut self.batcher_address,
+                &self.batcher_private_key,
+            ),
+            (
+                "sequencer",
+                &mut self.sequencer_address,
+                &self.sequencer_private_key,
+            ),
+            (
+                "proposer",
+                &mut self.proposer_address,
+                &self.proposer_private_key,
+            ),
+            (
+                "deployer",
+                &mut self.deployer_address,
+                &self.deployer_private_key,
+            ),
+            (
+                "challenger",
+                &mut self.challenger_address,
+                &self.challenger_private_key,
+            ),
+        ]
+    }
+
     pub fn null() -> Self {
         Self {
             admin_address: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
@@ -89,3 +243,62 @@ impl AccountsConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::AccountsConfig;
+
+    // anvil first and second accounts
+    const KEY_0: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ADDRESS_0: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const KEY_1: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+    const ADDRESS_1: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    #[test]
+    fn should_derive_missing_addresses() {
+        let mut accounts = AccountsConfig::null();
+        accounts.batcher_address = "".to_string();
+        accounts.batcher_private_key = KEY_1.to_string();
+
+        accounts.resolve().unwrap();
+
+        assert_eq!(accounts.batcher_address, ADDRESS_1);
+        assert_eq!(accounts.admin_address, ADDRESS_0);
+    }
+
+    #[test]
+    fn should_fail_when_address_does_not_match_key() {
+        let mut accounts = AccountsConfig::null();
+        accounts.proposer_private_key = KEY_1.to_string();
+
+        let error = accounts.resolve().unwrap_err().to_string();
+
+        assert_eq!(
+            error,
+            format!(
+                "Accounts don't match their private keys:\n  - proposer_address is {} but PROPOSER_PRIVATE_KEY belongs to {}",
+                ADDRESS_0, ADDRESS_1
+            )
+        );
+    }
+
+    #[test]
+    fn should_report_reused_keys() {
+        let mut accounts = AccountsConfig::null();
+        for private_key in [
+            &mut accounts.batcher_private_key,
+            &mut accounts.sequencer_private_key,
+        ] {
+            *private_key = KEY_1.to_string();
+        }
+        accounts.admin_private_key = KEY_0.trim_start_matches("0x").to_uppercase();
+
+        assert_eq!(
+            accounts.reused_keys(),
+            vec![
+                vec!["admin", "proposer", "deployer", "challenger"],
+                vec!["batcher", "sequencer"]
+            ]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ArtifactsConfig {
@@ -7,40 +8,95 @@ pub struct ArtifactsConfig {
     pub contracts: ArtifactConfig,
     pub batcher: ArtifactConfig,
     pub proposer: ArtifactConfig,
+    /// configs written before the explorer was built from source get the upstream one
+    #[serde(default = "ArtifactsConfig::default_explorer")]
+    pub explorer: ArtifactConfig,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct ArtifactConfig {
+    /// github `owner/repo`, or any url git can clone
+    #[serde(default)]
     pub source_repo: String,
+    /// tag to clone, kept for configs written before source_ref
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub source_tag: String,
+    /// branch, tag or full commit sha to clone
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_ref: Option<String>,
+    /// local checkout to build from instead of cloning, absolute or relative to the project root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
 }
 
 pub const INFRA_SOURCE_REPO: &str = "wakeuplabs-io/op-ruaas";
 pub const INFRA_SOURCE_REPO_VERSION: &str = "v0.0.12";
 
+impl ArtifactConfig {
+    pub fn source_ref(&self) -> &str {
+        self.source_ref.as_deref().unwrap_or(&self.source_tag)
+    }
+
+    /// Same config with source_path made absolute against the project root
+    pub fn with_root(&self, root: &Path) -> Self {
+        Self {
+            source_path: self
+                .source_path
+                .as_ref()
+                .map(|path| root.join(path).to_string_lossy().to_string()),
+            ..self.clone()
+        }
+    }
+}
+
 impl ArtifactsConfig {
+    pub fn all(&self) -> [(&'static str, &ArtifactConfig); 6] {
+        [
+            ("node", &self.node),
+            ("geth", &self.geth),
+            ("contracts", &self.contracts),
+            ("batcher", &self.batcher),
+            ("proposer", &self.proposer),
+            ("explorer", &self.explorer),
+        ]
+    }
+
+    fn default_explorer() -> ArtifactConfig {
+        ArtifactConfig {
+            source_repo: String::from("blockscout/blockscout"),
+            source_ref: Some(String::from("v6.9.2-beta")),
+            ..Default::default()
+        }
+    }
+
     pub fn null() -> Self {
         Self {
             node: ArtifactConfig {
                 source_repo: String::from("ethereum-optimism/optimism"),
-                source_tag: String::from("op-node/v1.9.4"),
+                source_ref: Some(String::from("op-node/v1.9.4")),
+                ..Default::default()
             },
             contracts: ArtifactConfig {
                 source_repo: String::from("ethereum-optimism/optimism"),
-                source_tag: String::from("op-contracts/v1.6.0"),
+                source_ref: Some(String::from("op-contracts/v1.6.0")),
+                ..Default::default()
             },
             batcher: ArtifactConfig {
                 source_repo: String::from("ethereum-optimism/optimism"),
-                source_tag: String::from("op-batcher/v1.9.4"),
+                source_ref: Some(String::from("op-batcher/v1.9.4")),
+                ..Default::default()
             },
             proposer: ArtifactConfig {
                 source_repo: String::from("ethereum-optimism/optimism"),
-                source_tag: String::from("op-proposer/v1.9.4"),
+                source_ref: Some(String::from("op-proposer/v1.9.4")),
+                ..Default::default()
             },
             geth: ArtifactConfig {
                 source_repo: String::from("ethereum-optimism/op-geth"),
-                source_tag: String::from("v1.101315.3"),
+                source_ref: Some(String::from("v1.101315.3")),
+                ..Default::default()
             },
+            explorer: Self::default_explorer(),
         }
     }
 }
@@ -2,8 +2,10 @@ pub mod accounts;
 pub mod artifacts;
 pub mod core;
 pub mod network;
+pub mod validator;
 
 pub use accounts::AccountsConfig;
 pub use artifacts::ArtifactsConfig;
 pub use core::CoreConfig;
 pub use network::NetworkConfig;
+pub use validator::*;
@@ -59,6 +59,9 @@ mod defaults {
 }
 
 impl NetworkConfig {
+    /// env variables read when a field is missing from the config
+    pub const ENV_VARS: [&'static str; 1] = ["L1_RPC_URL"];
+
     pub fn null() -> Self {
         Self {
             l1_rpc_url: "http://127.0.0.1:8545".to_string(),
@@ -108,7 +111,7 @@ impl NetworkConfig {
             eip1559_denominator_canyon: 250,
             l2_genesis_canyon_time_offset: "0x40".to_string(),
             l1_use_clique: true,
-            batch_inbox_address: "0xff69000000000000000000000000001201101712".to_string(),
+            batch_inbox_address: "0xfF69000000000000000000000000001201101712".to_string(),
         }
     }
 }
@@ -0,0 +1,343 @@
+use crate::config::{AccountsConfig, ArtifactsConfig, CoreConfig, NetworkConfig};
+use sha3::{Digest, Keccak256};
+use std::fmt;
+
+#[derive(Default)]
+pub struct ConfigValidator;
+
+/// Every problem found in a config, so they can all be fixed at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigValidationError {
+    pub problems: Vec<String>,
+}
+
+pub trait TConfigValidator: Send + Sync {
+    fn validate(&self, config: &CoreConfig) -> Result<(), ConfigValidationError>;
+}
+
+// implementations ===================================================
+
+impl ConfigValidator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn validate_network(&self, network: &NetworkConfig, problems: &mut Vec<String>) {
+        // chain
+        if network.l1_chain_id == network.l2_chain_id {
+            problems.push(format!(
+                "network.l1_chain_id and network.l2_chain_id must differ, both are {}",
+                network.l1_chain_id
+            ));
+        }
+        if network.l2_chain_id == 0 {
+            problems.push("network.l2_chain_id must be greater than 0".to_string());
+        }
+
+        // block times
+        if network.l1_block_time == 0 {
+            problems.push("network.l1_block_time must be greater than 0".to_string());
+        }
+        if network.l2_block_time == 0 {
+            problems.push("network.l2_block_time must be greater than 0".to_string());
+        }
+        if network.l2_block_time > network.l1_block_time {
+            problems.push(format!(
+                "network.l2_block_time ({}) can't be greater than network.l1_block_time ({})",
+                network.l2_block_time, network.l1_block_time
+            ));
+        }
+
+        // sequencing, drift is in seconds while the window is in l1 blocks
+        let sequencer_window_seconds = network.sequencer_window_size as u64 * network.l1_block_time as u64;
+        if network.max_sequencer_drift as u64 >= sequencer_window_seconds {
+            problems.push(format!(
+                "network.max_sequencer_drift ({}s) must be lower than the sequencer window, network.sequencer_window_size * network.l1_block_time ({}s)",
+                network.max_sequencer_drift, sequencer_window_seconds
+            ));
+        }
+        if network.channel_timeout == 0 {
+            problems.push("network.channel_timeout must be greater than 0".to_string());
+        }
+        if network.l2_output_oracle_submission_interval == 0 {
+            problems.push("network.l2_output_oracle_submission_interval must be greater than 0".to_string());
+        }
+
+        // fault proofs
+        if network.fault_game_split_depth >= network.fault_game_max_depth {
+            problems.push(format!(
+                "network.fault_game_split_depth ({}) must be lower than network.fault_game_max_depth ({})",
+                network.fault_game_split_depth, network.fault_game_max_depth
+            ));
+        }
+
+        // fee market
+        if network.eip1559_denominator == 0 {
+            problems.push("network.eip1559_denominator must be greater than 0".to_string());
+        }
+        if network.eip1559_denominator_canyon == 0 {
+            problems.push("network.eip1559_denominator_canyon must be greater than 0".to_string());
+        }
+        if network.eip1559_elasticity == 0 {
+            problems.push("network.eip1559_elasticity must be greater than 0".to_string());
+        }
+
+        // numeric strings
+        for (field, value) in [
+            (
+                "network.base_fee_vault_minimum_withdrawal_amount",
+                &network.base_fee_vault_minimum_withdrawal_amount,
+            ),
+            (
+                "network.l1_fee_vault_minimum_withdrawal_amount",
+                &network.l1_fee_vault_minimum_withdrawal_amount,
+            ),
+            (
+                "network.sequencer_fee_vault_minimum_withdrawal_amount",
+                &network.sequencer_fee_vault_minimum_withdrawal_amount,
+            ),
+            (
+                "network.l2_genesis_block_gas_limit",
+                &network.l2_genesis_block_gas_limit,
+            ),
+            (
+                "network.l2_genesis_block_base_fee_per_gas",
+                &network.l2_genesis_block_base_fee_per_gas,
+            ),
+            (
+                "network.l2_genesis_regolith_time_offset",
+                &network.l2_genesis_regolith_time_offset,
+            ),
+            (
+                "network.l2_genesis_canyon_time_offset",
+                &network.l2_genesis_canyon_time_offset,
+            ),
+        ] {
+            if !is_numeric(value) {
+                problems.push(format!(
+                    "{} must be a decimal or 0x prefixed hex number, got {:?}",
+                    field, value
+                ));
+            }
+        }
+
+        // 32 bytes hashes
+        for (field, value) in [
+            (
+                "network.required_protocol_version",
+                &network.required_protocol_version,
+            ),
+            (
+                "network.recommended_protocol_version",
+                &network.recommended_protocol_version,
+            ),
+            (
+                "network.fault_game_absolute_prestate",
+                &network.fault_game_absolute_prestate,
+            ),
+            (
+                "network.fault_game_genesis_output_root",
+                &network.fault_game_genesis_output_root,
+            ),
+        ] {
+            if !is_hex_of_length(value, 64) {
+                problems.push(format!(
+                    "{} must be a 0x prefixed 32 bytes hex, got {:?}",
+                    field, value
+                ));
+            }
+        }
+
+        validate_address(
+            "network.batch_inbox_address",
+            &network.batch_inbox_address,
+            problems,
+        );
+    }
+
+    fn validate_accounts(&self, accounts: &AccountsConfig, problems: &mut Vec<String>) {
+        for (field, value) in [
+            ("accounts.admin_address", &accounts.admin_address),
+            ("accounts.batcher_address", &accounts.batcher_address),
+            ("accounts.sequencer_address", &accounts.sequencer_address),
+            ("accounts.proposer_address", &accounts.proposer_address),
+            ("accounts.deployer_address", &accounts.deployer_address),
+            ("accounts.challenger_address", &accounts.challenger_address),
+        ] {
+            validate_address(field, value, problems);
+        }
+    }
+
+    fn validate_artifacts(&self, artifacts: &ArtifactsConfig, problems: &mut Vec<String>) {
+        for (name, artifact) in artifacts.all() {
+            let has_ref = artifact.source_ref.is_some() || !artifact.source_tag.is_empty();
+
+            if artifact.source_ref.is_some() && !artifact.source_tag.is_empty() {
+                problems.push(format!(
+                    "artifacts.{name} sets both source_ref and source_tag, source_ref replaces source_tag"
+                ));
+            }
+            match (&artifact.source_path, has_ref) {
+                (Some(_), true) => problems.push(format!(
+                    "artifacts.{name} sets source_path along with a ref, local checkouts are used as they are"
+                )),
+                (None, false) => problems.push(format!(
+                    "artifacts.{name} needs a source_ref or a source_path"
+                )),
+                (None, true) if artifact.source_repo.is_empty() => problems.push(format!(
+                    "artifacts.{name}.source_repo is required to clone source_ref"
+                )),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl TConfigValidator for ConfigValidator {
+    fn validate(&self, config: &CoreConfig) -> Result<(), ConfigValidationError> {
+        let mut problems = vec![];
+
+        self.validate_accounts(&config.accounts, &mut problems);
+        self.validate_network(&config.network, &mut problems);
+        self.validate_artifacts(&config.artifacts, &mut problems);
+
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigValidationError { problems }),
+        }
+    }
+}
+
+impl fmt::Display for ConfigValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid config, found {} problems:", self.problems.len())?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigValidationError {}
+
+fn is_numeric(value: &str) -> bool {
+    match value.strip_prefix("0x") {
+        Some(hex) => !hex.is_empty() && hex.len() <= 64 && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()),
+    }
+}
+
+fn is_hex_of_length(value: &str, length: usize) -> bool {
+    value
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == length && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn validate_address(field: &str, value: &str, problems: &mut Vec<String>) {
+    if !is_hex_of_length(value, 40) {
+        problems.push(format!(
+            "{} must be a 0x prefixed 20 bytes address, got {:?}",
+            field, value
+        ));
+        return;
+    }
+
+    let checksummed = to_checksum_address(value);
+    if value != checksummed {
+        problems.push(format!(
+            "{} is not checksummed, expected {}",
+            field, checksummed
+        ));
+    }
+}
+
+/// EIP-55 mixed case encoding of an address
+pub fn to_checksum_address(address: &str) -> String {
+    let address = address.trim_start_matches("0x").to_lowercase();
+    let hash = Keccak256::digest(address.as_bytes());
+
+    let checksummed: String = address
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            match nibble >= 8 {
+                true => c.to_ascii_uppercase(),
+                false => c,
+            }
+        })
+        .collect();
+
+    format!("0x{}", checksummed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_checksum_address, ConfigValidator, TConfigValidator};
+    use crate::config::CoreConfig;
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_eq!(
+            ConfigValidator::new().validate(&CoreConfig::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn should_checksum_addresses() {
+        assert_eq!(
+            to_checksum_address("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+    }
+
+    #[test]
+    fn should_report_every_problem() {
+        let mut config = CoreConfig::default();
+        config.network.fault_game_split_depth = 30;
+        config.network.l2_chain_id = config.network.l1_chain_id;
+        config.network.sequencer_window_size = 10;
+        config.network.l2_block_time = 24;
+        config.network.l2_genesis_block_gas_limit = "30M".to_string();
+        config.accounts.batcher_address = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".to_string();
+        config.accounts.proposer_address = "0x1234".to_string();
+
+        let problems = ConfigValidator::new()
+            .validate(&config)
+            .unwrap_err()
+            .problems;
+
+        assert_eq!(problems.len(), 7);
+        assert!(problems[0].starts_with("accounts.batcher_address is not checksummed"));
+        assert!(problems[1].starts_with("accounts.proposer_address must be a 0x prefixed 20 bytes address"));
+        assert!(problems[2].starts_with("network.l1_chain_id and network.l2_chain_id must differ"));
+        assert!(problems[3].starts_with("network.l2_block_time (24) can't be greater"));
+        assert!(problems[4].starts_with("network.max_sequencer_drift (600s) must be lower"));
+        assert!(problems[5].starts_with("network.fault_game_split_depth (30) must be lower"));
+        assert!(problems[6].starts_with("network.l2_genesis_block_gas_limit must be"));
+    }
+
+    #[test]
+    fn should_require_a_single_artifact_source() {
+        let mut config = CoreConfig::default();
+        config.artifacts.node.source_path = Some("../optimism".to_string());
+        config.artifacts.geth.source_ref = None;
+        config.artifacts.batcher.source_tag = "op-batcher/v1.9.4".to_string();
+
+        let problems = ConfigValidator::new()
+            .validate(&config)
+            .unwrap_err()
+            .problems;
+
+        assert_eq!(
+            problems,
+            vec![
+                "artifacts.node sets source_path along with a ref, local checkouts are used as they are",
+                "artifacts.geth needs a source_ref or a source_path",
+                "artifacts.batcher sets both source_ref and source_tag, source_ref replaces source_tag",
+            ]
+        );
+    }
+}
@@ -10,16 +10,20 @@ pub struct ArtifactData {
     pub context: PathBuf,
     pub dockerfile: PathBuf,
     pub source_url: String,
-    pub source_tag: String,
+    /// branch, tag or commit sha
+    pub source_ref: String,
+    /// local checkout used instead of cloning source_url
+    pub source_path: Option<PathBuf>,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
+#[derive(Hash, Eq, PartialEq, Debug, Clone)]
 pub enum ArtifactKind {
     Batcher,
     Node,
     Contracts,
     Proposer,
     Geth,
+    Explorer,
 }
 
 impl ArtifactKind {
@@ -30,8 +34,20 @@ impl ArtifactKind {
             ArtifactKind::Contracts,
             ArtifactKind::Proposer,
             ArtifactKind::Geth,
+            ArtifactKind::Explorer,
         ]
     }
+
+    pub const fn artifact_name(&self) -> &'static str {
+        match self {
+            ArtifactKind::Batcher => "op-batcher",
+            ArtifactKind::Node => "op-node",
+            ArtifactKind::Contracts => "op-contracts",
+            ArtifactKind::Proposer => "op-proposer",
+            ArtifactKind::Geth => "op-geth",
+            ArtifactKind::Explorer => "op-explorer",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -41,6 +57,7 @@ pub enum Artifact {
     Contracts(ArtifactData),
     Proposer(ArtifactData),
     Geth(ArtifactData),
+    Explorer(ArtifactData),
 }
 
 impl fmt::Display for Artifact {
@@ -51,16 +68,38 @@ impl fmt::Display for Artifact {
             Artifact::Contracts(_data) => write!(f, "Contracts"),
             Artifact::Proposer(_data) => write!(f, "Proposer"),
             Artifact::Geth(_data) => write!(f, "Geth"),
+            Artifact::Explorer(_data) => write!(f, "Explorer"),
         }
     }
 }
 
 pub struct ArtifactFactory {}
 
+/// How far along the download of a source is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PullProgress {
+    pub received_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+}
+
+pub type OnPullProgress = dyn Fn(PullProgress) + Sync;
+
 #[automock]
 pub trait TArtifactSourceRepository: Send + Sync {
-    fn pull(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>>;
+    fn pull(&self, artifact: &Artifact, on_progress: &OnPullProgress) -> Result<(), Box<dyn std::error::Error>>;
+    /// Moves an existing source to the artifact's ref, refusing uncommitted changes unless `force`
+    fn update(
+        &self,
+        artifact: &Artifact,
+        force: bool,
+        on_progress: &OnPullProgress,
+    ) -> Result<(), Box<dyn std::error::Error>>;
     fn exists(&self, artifact: &Artifact) -> bool;
+    /// Commit the source is checked out at
+    fn commit(&self, artifact: &Artifact) -> Result<String, Box<dyn std::error::Error>>;
+    /// Identifies the current content of the source tree, changes with every commit or uncommitted edit
+    fn tree_state(&self, artifact: &Artifact) -> Result<String, Box<dyn std::error::Error>>;
 }
 
 pub trait TArtifactFactory {
@@ -71,7 +110,15 @@ pub trait TArtifactFactory {
 #[automock]
 pub trait TArtifactRepository: Send + Sync {
     fn exists(&self, artifact: &Artifact) -> bool;
-    fn create(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>>;
+    /// Fingerprint the local image was built with, if there's one
+    fn fingerprint(&self, artifact: &Artifact) -> Option<String>;
+    /// Builds the artifact image labeled with its fingerprint, with buildx when platforms are given
+    fn create(
+        &self,
+        artifact: &Artifact,
+        platforms: &[String],
+        fingerprint: &str,
+    ) -> Result<(), Box<dyn std::error::Error>>;
 }
 
 // implementations ==========================================
@@ -83,24 +130,23 @@ impl ArtifactData {
             context: context.to_path_buf(),
             dockerfile: dockerfile.to_path_buf(),
             source_url: config.source_repo.clone(),
-            source_tag: config.source_tag.clone(),
+            source_ref: config.source_ref().to_string(),
+            source_path: config.source_path.as_ref().map(PathBuf::from),
         }
     }
 }
 
 impl Artifact {
     pub fn new(kind: ArtifactKind, source: &PathBuf, dockerfile: &PathBuf, config: &ArtifactConfig) -> Self {
+        let data = ArtifactData::new(kind.artifact_name(), source, dockerfile, config);
+
         match kind {
-            ArtifactKind::Batcher => Artifact::Batcher(ArtifactData::new("op-batcher", source, dockerfile, config)),
-            ArtifactKind::Node => Artifact::Node(ArtifactData::new("op-node", source, dockerfile, config)),
-            ArtifactKind::Contracts => Artifact::Contracts(ArtifactData::new(
-                "op-contracts",
-                source,
-                dockerfile,
-                config,
-            )),
-            ArtifactKind::Proposer => Artifact::Proposer(ArtifactData::new("op-proposer", source, dockerfile, config)),
-            ArtifactKind::Geth => Artifact::Geth(ArtifactData::new("op-geth", source, dockerfile, config)),
+            ArtifactKind::Batcher => Artifact::Batcher(data),
+            ArtifactKind::Node => Artifact::Node(data),
+            ArtifactKind::Contracts => Artifact::Contracts(data),
+            ArtifactKind::Proposer => Artifact::Proposer(data),
+            ArtifactKind::Geth => Artifact::Geth(data),
+            ArtifactKind::Explorer => Artifact::Explorer(data),
         }
     }
 
@@ -110,6 +156,7 @@ impl Artifact {
             | Artifact::Node(data)
             | Artifact::Proposer(data)
             | Artifact::Geth(data)
+            | Artifact::Explorer(data)
             | Artifact::Contracts(data) => &data.name,
         }
     }
@@ -120,7 +167,19 @@ impl Artifact {
             | Artifact::Node(data)
             | Artifact::Proposer(data)
             | Artifact::Geth(data)
-            | Artifact::Contracts(data) => (&data.source_url, &data.source_tag),
+            | Artifact::Explorer(data)
+            | Artifact::Contracts(data) => (&data.source_url, &data.source_ref),
+        }
+    }
+
+    pub fn source_path(&self) -> Option<&PathBuf> {
+        match self {
+            Artifact::Batcher(data)
+            | Artifact::Node(data)
+            | Artifact::Proposer(data)
+            | Artifact::Geth(data)
+            | Artifact::Explorer(data)
+            | Artifact::Contracts(data) => data.source_path.as_ref(),
         }
     }
 
@@ -130,6 +189,7 @@ impl Artifact {
             | Artifact::Node(data)
             | Artifact::Proposer(data)
             | Artifact::Geth(data)
+            | Artifact::Explorer(data)
             | Artifact::Contracts(data) => &data.context,
         }
     }
@@ -140,6 +200,7 @@ impl Artifact {
             | Artifact::Node(data)
             | Artifact::Proposer(data)
             | Artifact::Geth(data)
+            | Artifact::Explorer(data)
             | Artifact::Contracts(data) => &data.dockerfile,
         }
     }
@@ -158,31 +219,37 @@ impl TArtifactFactory for ArtifactFactory {
                 ArtifactKind::Batcher,
                 &project.src.batcher,
                 &project.infra.docker.batcher,
-                &config.artifacts.batcher,
+                &config.artifacts.batcher.with_root(&project.root),
             )),
             ArtifactKind::Contracts => Arc::new(Artifact::new(
                 ArtifactKind::Contracts,
                 &project.src.contracts,
                 &project.infra.docker.contracts,
-                &config.artifacts.contracts,
+                &config.artifacts.contracts.with_root(&project.root),
             )),
             ArtifactKind::Geth => Arc::new(Artifact::new(
                 ArtifactKind::Geth,
                 &project.src.geth,
                 &project.infra.docker.geth,
-                &config.artifacts.geth,
+                &config.artifacts.geth.with_root(&project.root),
             )),
             ArtifactKind::Node => Arc::new(Artifact::new(
                 ArtifactKind::Node,
                 &project.src.node,
                 &project.infra.docker.node,
-                &config.artifacts.node,
+                &config.artifacts.node.with_root(&project.root),
             )),
             ArtifactKind::Proposer => Arc::new(Artifact::new(
                 ArtifactKind::Proposer,
                 &project.src.proposer,
                 &project.infra.docker.proposer,
-                &config.artifacts.proposer,
+                &config.artifacts.proposer.with_root(&project.root),
+            )),
+            ArtifactKind::Explorer => Arc::new(Artifact::new(
+                ArtifactKind::Explorer,
+                &project.src.explorer,
+                &project.infra.docker.explorer,
+                &config.artifacts.explorer.with_root(&project.root),
             )),
         }
     }
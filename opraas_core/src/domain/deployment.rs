@@ -1,10 +1,16 @@
 use crate::config::{AccountsConfig, NetworkConfig};
-use std::path::PathBuf;
+use mockall::automock;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, Clone)]
 pub struct Deployment {
     pub name: String,
     pub release_name: String,
+    /// artifact name to release tag, for artifacts upgraded on their own
+    pub release_overrides: HashMap<String, String>,
     pub registry_url: String,
     pub network_config: NetworkConfig,
     pub accounts_config: AccountsConfig,
@@ -12,9 +18,23 @@ pub struct Deployment {
     pub infra_artifacts: Option<PathBuf>,
 }
 
+// mockall needs the lifetimes of references nested in Option spelled out
+#[allow(clippy::needless_lifetimes)]
+#[automock]
 pub trait TDeploymentRepository: Send + Sync {
     fn save(&self, deployment: &mut Deployment) -> Result<(), Box<dyn std::error::Error>>;
     fn find(&self, name: &str) -> Result<Option<Deployment>, Box<dyn std::error::Error>>;
+    fn list(&self) -> Result<Vec<Deployment>, Box<dyn std::error::Error>>;
+    fn archive(&self, name: &str) -> Result<(), Box<dyn std::error::Error>>;
+    /// Packs the deployment into a single bundle at `dest`, encrypted when a passphrase is given
+    fn export<'a>(&self, name: &str, dest: &Path, passphrase: Option<&'a str>) -> Result<(), Box<dyn std::error::Error>>;
+    /// Verifies and restores a bundle. Fails if the deployment already exists unless `force` is set.
+    fn import<'a>(
+        &self,
+        src: &Path,
+        passphrase: Option<&'a str>,
+        force: bool,
+    ) -> Result<Deployment, Box<dyn std::error::Error>>;
 }
 
 // implementations ========================================================
@@ -30,6 +50,7 @@ impl Deployment {
         Self {
             name,
             release_name,
+            release_overrides: HashMap::new(),
             registry_url,
             network_config,
             accounts_config,
@@ -38,6 +59,12 @@ impl Deployment {
         }
     }
 
+    pub fn release_for(&self, artifact_name: &str) -> &str {
+        self.release_overrides
+            .get(artifact_name)
+            .unwrap_or(&self.release_name)
+    }
+
     pub fn write_contracts_config(&self, path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         let json = format!(
             r#"{{
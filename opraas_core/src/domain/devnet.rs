@@ -0,0 +1,29 @@
+use mockall::automock;
+use serde::{Deserialize, Serialize};
+
+/// What a detached devnet left running, enough to check on it and tear it down from another process
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DevnetState {
+    /// geth or anvil
+    #[serde(default = "default_l1_node")]
+    pub l1_node: String,
+    pub l1_container: String,
+    pub l1_port: u64,
+    pub helm_release: String,
+    pub namespace: String,
+    pub deployment_name: String,
+    /// unix timestamp
+    pub started_at: u64,
+}
+
+// states recorded before anvil was supported
+fn default_l1_node() -> String {
+    "geth".to_string()
+}
+
+#[automock]
+pub trait TDevnetStateRepository: Send + Sync {
+    fn save(&self, state: &DevnetState) -> Result<(), Box<dyn std::error::Error>>;
+    fn find(&self) -> Result<Option<DevnetState>, Box<dyn std::error::Error>>;
+    fn delete(&self) -> Result<(), Box<dyn std::error::Error>>;
+}
@@ -1,11 +1,13 @@
 pub mod artifact;
 pub mod deployment;
+pub mod devnet;
 pub mod project;
 pub mod release;
 pub mod stack;
 
 pub use artifact::*;
 pub use deployment::*;
+pub use devnet::*;
 pub use project::*;
 pub use release::*;
 pub use stack::*;
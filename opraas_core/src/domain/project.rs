@@ -4,6 +4,8 @@ use std::{env, path::PathBuf};
 pub struct Project {
     pub root: PathBuf,
     pub config: PathBuf,
+    /// release manifests, one per release name
+    pub releases: PathBuf,
     pub infra: Infra,
     pub src: Src,
 }
@@ -87,6 +89,7 @@ impl TProjectFactory for ProjectFactory {
         Project {
             root: root.clone(),
             config: root.join("config.toml"),
+            releases: root.join("releases"),
             infra: Infra {
                 root: root.join("infra"),
                 aws: root.join("infra").join("aws"),
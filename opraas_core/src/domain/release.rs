@@ -9,6 +9,33 @@ pub struct Release {
     pub artifact_name: String,
     pub artifact_tag: String,
     pub registry_url: String,
+    /// platforms of the pushed manifest list, empty for single platform releases of the local build
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    /// digest of the pushed image or manifest list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+/// Every artifact released under a name, written to `releases/<name>.json`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReleaseManifest {
+    pub name: String,
+    pub artifacts: Vec<ReleasedArtifact>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReleasedArtifact {
+    pub artifact_name: String,
+    pub registry_url: String,
+    pub digest: String,
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    pub source_repo: String,
+    /// commit the source was checked out at, empty if unknown
+    pub commit: String,
+    /// unix timestamp of the image build, 0 if unknown
+    pub built_at: u64,
 }
 
 #[automock]
@@ -18,8 +45,31 @@ pub trait TReleaseRepository: Send + Sync {
         artifact: &Artifact,
         release_name: &str,
         registry_url: &str,
+        platforms: &[String],
     ) -> Result<Release, Box<dyn std::error::Error>>;
     fn pull(&self, release: &Release) -> Result<(), Box<dyn std::error::Error>>;
+    /// Copies the artifact's `from` release to another registry or name, failing if the pushed digest differs
+    fn promote(
+        &self,
+        artifact: &Artifact,
+        from: &Release,
+        registry_url: &str,
+        release_name: &str,
+    ) -> Result<Release, Box<dyn std::error::Error>>;
+}
+
+#[automock]
+pub trait TReleaseManifestRepository: Send + Sync {
+    fn save(&self, manifest: &ReleaseManifest) -> Result<(), Box<dyn std::error::Error>>;
+    fn find(&self, name: &str) -> Result<Option<ReleaseManifest>, Box<dyn std::error::Error>>;
+}
+
+#[automock]
+pub trait TReleaseRegistry: Send + Sync {
+    /// Tags pushed for the artifact to the registry, empty when there's no repository for it
+    fn tags(&self, registry_url: &str, artifact_name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+    /// Whether the registry has a manifest for the release, by digest when it's pinned
+    fn exists(&self, release: &Release) -> Result<bool, Box<dyn std::error::Error>>;
 }
 
 pub trait TReleaseRunner: Send + Sync {
@@ -49,6 +99,8 @@ impl Release {
             artifact_name,
             artifact_tag,
             registry_url,
+            platforms: vec![],
+            digest: None,
         }
     }
 
@@ -67,6 +119,8 @@ impl Release {
             artifact_name: artifact.name().to_string(),
             artifact_tag: release_name.to_string(),
             registry_url: registry_url.to_string(),
+            platforms: vec![],
+            digest: None,
         }
     }
 
@@ -76,4 +130,85 @@ impl Release {
             self.registry_url, self.artifact_name, self.artifact_tag
         )
     }
+
+    /// Pins the release to the digest its manifest recorded for this registry, if there's one
+    pub fn pinned_by(mut self, manifest: Option<&ReleaseManifest>) -> Self {
+        if let Some(released) = manifest.and_then(|manifest| manifest.find(&self.artifact_name, &self.registry_url)) {
+            self.digest = Some(released.digest.clone());
+            self.platforms = released.platforms.clone();
+        }
+
+        self
+    }
+
+    /// Same as uri but pinned to the digest when it's known, a tag can be pushed again
+    pub fn pinned_uri(&self) -> String {
+        match &self.digest {
+            Some(digest) => format!("{}/{}@{}", self.registry_url, self.artifact_name, digest),
+            None => self.uri(),
+        }
+    }
+}
+
+impl ReleaseManifest {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            artifacts: vec![],
+        }
+    }
+
+    /// Records the artifact, replacing a previous release of it to the same registry
+    pub fn upsert(&mut self, artifact: ReleasedArtifact) {
+        self.artifacts.retain(|released| {
+            released.artifact_name != artifact.artifact_name || released.registry_url != artifact.registry_url
+        });
+        self.artifacts.push(artifact);
+    }
+
+    /// Digest the artifact was released with to `registry_url`, if it was
+    pub fn digest_for(&self, artifact_name: &str, registry_url: &str) -> Option<&str> {
+        self.find(artifact_name, registry_url)
+            .map(|released| released.digest.as_str())
+    }
+
+    pub fn find(&self, artifact_name: &str, registry_url: &str) -> Option<&ReleasedArtifact> {
+        self.artifacts
+            .iter()
+            .find(|released| released.artifact_name == artifact_name && released.registry_url == registry_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn released(artifact_name: &str, registry_url: &str, digest: &str) -> ReleasedArtifact {
+        ReleasedArtifact {
+            artifact_name: artifact_name.to_string(),
+            registry_url: registry_url.to_string(),
+            digest: digest.to_string(),
+            platforms: vec![],
+            source_repo: "ethereum-optimism/optimism".to_string(),
+            commit: "abc".to_string(),
+            built_at: 1700000000,
+        }
+    }
+
+    #[test]
+    fn should_keep_one_entry_per_artifact_and_registry() {
+        let mut manifest = ReleaseManifest::new("v0.1.0");
+
+        manifest.upsert(released("op-node", "staging", "sha256:1"));
+        manifest.upsert(released("op-node", "production", "sha256:2"));
+        manifest.upsert(released("op-node", "staging", "sha256:3"));
+
+        assert_eq!(manifest.artifacts.len(), 2);
+        assert_eq!(manifest.digest_for("op-node", "staging"), Some("sha256:3"));
+        assert_eq!(
+            manifest.digest_for("op-node", "production"),
+            Some("sha256:2")
+        );
+        assert_eq!(manifest.digest_for("op-geth", "staging"), None);
+    }
 }
@@ -1,21 +1,56 @@
+use mockall::automock;
 use std::path::PathBuf;
 
 use crate::infra::deployment::InMemoryDeploymentRepository;
 
-use super::{Deployment, Project, TDeploymentRepository};
+use super::{ArtifactKind, Deployment, Project, TDeploymentRepository};
 
 pub struct Stack {
     pub helm: PathBuf,
     pub aws: PathBuf,
+    /// release manifests to pin the deployment images with
+    pub releases: PathBuf,
     pub deployment: Option<Deployment>,
 }
 
+#[automock]
 pub trait TStackInfraRepository: Send + Sync {
     fn pull(&self, stack: &Stack) -> Result<(), Box<dyn std::error::Error>>;
 }
 
+#[automock]
 pub trait TStackInfraDeployer: Send + Sync {
     fn deploy(&self, stack: &Stack) -> Result<Deployment, Box<dyn std::error::Error>>;
+    fn destroy(&self, stack: &Stack) -> Result<(), Box<dyn std::error::Error>>;
+    fn upgrade(&self, stack: &Stack, artifacts: &[ArtifactKind]) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// Stack components running their own pods
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StackComponent {
+    Node,
+    Geth,
+    Batcher,
+    Proposer,
+    Explorer,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogsOptions {
+    pub follow: bool,
+    /// relative duration like 5s, 2m or 3h
+    pub since: Option<String>,
+}
+
+pub trait TStackLogs: Send + Sync {
+    /// Streams the logs of every pod of the components, calling `on_line` with the pod name and the log line
+    fn logs(
+        &self,
+        stack: &Stack,
+        components: &[StackComponent],
+        options: &LogsOptions,
+        on_line: &(dyn Fn(&str, &str) + Sync),
+    ) -> Result<(), Box<dyn std::error::Error>>;
 }
 
 pub trait TStackRunner {
@@ -25,11 +60,24 @@ pub trait TStackRunner {
 
 // implementations ==================================================
 
+impl StackComponent {
+    pub const fn all() -> &'static [StackComponent] {
+        &[
+            StackComponent::Node,
+            StackComponent::Geth,
+            StackComponent::Batcher,
+            StackComponent::Proposer,
+            StackComponent::Explorer,
+        ]
+    }
+}
+
 impl Stack {
-    pub fn new(helm: PathBuf, aws: PathBuf, deployment: Option<Deployment>) -> Self {
+    pub fn new(helm: PathBuf, aws: PathBuf, releases: PathBuf, deployment: Option<Deployment>) -> Self {
         Self {
             helm,
             aws,
+            releases,
             deployment,
         }
     }
@@ -41,6 +89,7 @@ impl Stack {
         Self {
             helm: project.infra.helm.clone(),
             aws: project.infra.aws.clone(),
+            releases: project.releases.clone(),
             deployment,
         }
     }
@@ -1,9 +1,12 @@
 use std::process::Command;
 
-use crate::{domain, system};
+use crate::{docker, domain, system};
 
 pub struct DockerArtifactRepository;
 
+// image label holding the build fingerprint
+const FINGERPRINT_LABEL: &str = "opruaas.fingerprint";
+
 // implementations ==========================================
 
 impl DockerArtifactRepository {
@@ -13,12 +16,32 @@ impl DockerArtifactRepository {
 }
 
 impl domain::TArtifactRepository for DockerArtifactRepository {
-    fn create(&self, artifact: &domain::Artifact) -> Result<(), Box<dyn std::error::Error>> {
+    fn create(
+        &self,
+        artifact: &domain::Artifact,
+        platforms: &[String],
+        fingerprint: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let label = format!("{}={}", FINGERPRINT_LABEL, fingerprint);
+
+        if !platforms.is_empty() {
+            return docker::buildx_build(
+                artifact.context(),
+                artifact.dockerfile(),
+                artifact.name(),
+                &[label],
+                platforms,
+                false,
+            );
+        }
+
         system::execute_command(
             Command::new("docker")
                 .arg("build")
                 .arg("-t")
                 .arg(artifact.name())
+                .arg("--label")
+                .arg(label)
                 .arg("-f")
                 .arg(artifact.dockerfile())
                 .arg(".")
@@ -40,4 +63,24 @@ impl domain::TArtifactRepository for DockerArtifactRepository {
         .unwrap()
         .is_empty()
     }
+
+    fn fingerprint(&self, artifact: &domain::Artifact) -> Option<String> {
+        let fingerprint = system::execute_command(
+            Command::new("docker")
+                .args(["image", "inspect", "--format"])
+                .arg(format!(
+                    "{{{{ index .Config.Labels \"{}\" }}}}",
+                    FINGERPRINT_LABEL
+                ))
+                .arg(artifact.name()),
+            true,
+        )
+        .ok()?;
+
+        // images without the label print <no value>
+        match fingerprint.trim() {
+            "" | "<no value>" => None,
+            fingerprint => Some(fingerprint.to_string()),
+        }
+    }
 }
@@ -1,71 +1,124 @@
 use crate::{
-    config::artifacts::{INFRA_SOURCE_REPO, INFRA_SOURCE_REPO_VERSION},
-    domain::{self, artifact::Artifact},
+    domain::{self, artifact::Artifact, OnPullProgress, PullProgress},
     git,
 };
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
 
 pub struct GitArtifactSourceRepository;
 
+// bare repositories next to the sources, checkouts of the same repository share its objects
+const OBJECTS_CACHE_DIRNAME: &str = ".objects";
+
+// the infra releases don't have the explorer nor cross-platform builds yet, so the dockerfiles ship with the binary,
+// from within the crate so it can still be packaged
+const BATCHER_DOCKERFILE: &str = include_str!("../../../docker/batcher.dockerfile");
+const CONTRACTS_DOCKERFILE: &str = include_str!("../../../docker/contracts.dockerfile");
+const PROPOSER_DOCKERFILE: &str = include_str!("../../../docker/proposer.dockerfile");
+const GETH_DOCKERFILE: &str = include_str!("../../../docker/geth.dockerfile");
+const NODE_DOCKERFILE: &str = include_str!("../../../docker/node.dockerfile");
+const EXPLORER_DOCKERFILE: &str = include_str!("../../../docker/explorer.dockerfile");
+
+/// What the source of an artifact was pulled from, written next to it as `<source>.source.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub source_repo: String,
+    pub source_ref: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<PathBuf>,
+    /// the commit source_ref resolved to
+    pub commit: String,
+}
+
+// implementations ==========================================
+
 impl GitArtifactSourceRepository {
     pub fn new() -> Self {
         Self
     }
+
+    fn record_source(&self, artifact: &Artifact, commit: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let (source_repo, source_ref) = artifact.source_info();
+        let record = SourceRecord {
+            source_repo: source_repo.to_string(),
+            source_ref: source_ref.to_string(),
+            source_path: artifact.source_path().cloned(),
+            commit: commit.to_string(),
+        };
+
+        std::fs::write(
+            artifact.context().with_extension("source.json"),
+            serde_json::to_string_pretty(&record)?,
+        )?;
+
+        Ok(())
+    }
 }
 
 impl domain::artifact::TArtifactSourceRepository for GitArtifactSourceRepository {
-    fn pull(&self, artifact: &Artifact) -> Result<(), Box<dyn std::error::Error>> {
-        let (source_repo, source_tag) = artifact.source_info();
+    fn pull(&self, artifact: &Artifact, on_progress: &OnPullProgress) -> Result<(), Box<dyn std::error::Error>> {
+        let (source_repo, source_ref) = artifact.source_info();
 
+        let commit = match artifact.source_path() {
+            Some(source_path) => git::link_checkout(source_path, artifact.context())?,
+            None => git::clone_ref(
+                source_repo,
+                source_ref,
+                artifact.context().as_path().to_str().unwrap(),
+                &artifact.context().with_file_name(OBJECTS_CACHE_DIRNAME),
+                &|progress| {
+                    on_progress(PullProgress {
+                        received_objects: progress.received_objects(),
+                        total_objects: progress.total_objects(),
+                        received_bytes: progress.received_bytes(),
+                    })
+                },
+            )?,
+        };
+        self.record_source(artifact, &commit)?;
 
-        git::clone_tag(
-            source_repo,
-            source_tag,
-            artifact.context().as_path().to_str().unwrap(),
-        )?;
+        // dockerfile for infra
+        let dockerfile = match artifact {
+            Artifact::Batcher(..) => BATCHER_DOCKERFILE,
+            Artifact::Contracts(..) => CONTRACTS_DOCKERFILE,
+            Artifact::Proposer(..) => PROPOSER_DOCKERFILE,
+            Artifact::Geth(..) => GETH_DOCKERFILE,
+            Artifact::Node(..) => NODE_DOCKERFILE,
+            Artifact::Explorer(..) => EXPLORER_DOCKERFILE,
+        };
+        fs::create_dir_all(artifact.dockerfile().parent().unwrap())?;
+        fs::write(artifact.dockerfile(), dockerfile)?;
+
+        Ok(())
+    }
 
-        // download dockerfile for infra
-        match artifact {
-            Artifact::Batcher(..) => {
-                git::download_release_asset(
-                    INFRA_SOURCE_REPO,
-                    INFRA_SOURCE_REPO_VERSION,
-                    "infra/docker/batcher.dockerfile",
-                    artifact.dockerfile().as_path().to_str().unwrap(),
-                )?;
-            }
-            Artifact::Contracts(..) => {
-                git::download_release_asset(
-                    INFRA_SOURCE_REPO,
-                    INFRA_SOURCE_REPO_VERSION,
-                    "infra/docker/contracts.dockerfile",
-                    artifact.dockerfile().as_path().to_str().unwrap(),
-                )?;
-            }
-            Artifact::Proposer(..) => {
-                git::download_release_asset(
-                    INFRA_SOURCE_REPO,
-                    INFRA_SOURCE_REPO_VERSION,
-                    "infra/docker/proposer.dockerfile",
-                    artifact.dockerfile().as_path().to_str().unwrap(),
-                )?;
-            }
-            Artifact::Geth(..) => {
-                git::download_release_asset(
-                    INFRA_SOURCE_REPO,
-                    INFRA_SOURCE_REPO_VERSION,
-                    "infra/docker/geth.dockerfile",
-                    artifact.dockerfile().as_path().to_str().unwrap(),
-                )?;
-            }
-            Artifact::Node(..) => {
-                git::download_release_asset(
-                    INFRA_SOURCE_REPO,
-                    INFRA_SOURCE_REPO_VERSION,
-                    "infra/docker/node.dockerfile",
-                    artifact.dockerfile().as_path().to_str().unwrap(),
-                )?;
-            }
+    fn update(
+        &self,
+        artifact: &Artifact,
+        force: bool,
+        on_progress: &OnPullProgress,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (source_repo, source_ref) = artifact.source_info();
+
+        // local checkouts are linked, they're moved by whoever owns them
+        let commit = match artifact.source_path() {
+            Some(_) => git::head_commit(artifact.context())?,
+            None => git::update_ref(
+                source_repo,
+                source_ref,
+                artifact.context(),
+                &artifact.context().with_file_name(OBJECTS_CACHE_DIRNAME),
+                force,
+                &|progress| {
+                    on_progress(PullProgress {
+                        received_objects: progress.received_objects(),
+                        total_objects: progress.total_objects(),
+                        received_bytes: progress.received_bytes(),
+                    })
+                },
+            )?,
         };
+        self.record_source(artifact, &commit)?;
 
         Ok(())
     }
@@ -73,4 +126,12 @@ impl domain::artifact::TArtifactSourceRepository for GitArtifactSourceRepository
     fn exists(&self, artifact: &Artifact) -> bool {
         artifact.context().exists()
     }
+
+    fn commit(&self, artifact: &Artifact) -> Result<String, Box<dyn std::error::Error>> {
+        git::head_commit(artifact.context())
+    }
+
+    fn tree_state(&self, artifact: &Artifact) -> Result<String, Box<dyn std::error::Error>> {
+        git::tree_state(artifact.context().as_path().to_str().unwrap())
+    }
 }
@@ -0,0 +1,280 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Read, Write},
+    path::{Component, Path},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+/// Describes the content of a deployment bundle, stored as manifest.json at its root
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub name: String,
+    pub created_at: u64,
+    /// file path, relative to the deployment root, to its sha256
+    pub files: BTreeMap<String, String>,
+}
+
+// bumped whenever the layout changes, imports reject versions they don't know
+pub const BUNDLE_VERSION: u32 = 1;
+pub const BUNDLE_EXTENSION: &str = "opruaas";
+
+const MANIFEST_FILENAME: &str = "manifest.json";
+
+// encrypted bundles are the magic followed by the scrypt salt, the aes-gcm nonce and the ciphertext
+const ENCRYPTED_MAGIC: &[u8] = b"OPRUAAS-ENC\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const SCRYPT_LOG_N: u8 = 15;
+
+// implementations ====================================
+
+/// Zips every file of the deployment at `depl_path` along with its manifest
+pub fn pack(depl_path: &Path, name: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut files = BTreeMap::new();
+    let mut zip = ZipWriter::new(Cursor::new(vec![]));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for path in list_files(depl_path)? {
+        let relative_path = path
+            .strip_prefix(depl_path)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let content = std::fs::read(&path)?;
+
+        zip.start_file(relative_path.as_str(), options)?;
+        zip.write_all(&content)?;
+        files.insert(relative_path, sha256(&content));
+    }
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        name: name.to_string(),
+        created_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        files,
+    };
+    zip.start_file(MANIFEST_FILENAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Verifies the bundle against its manifest and extracts its files into `dest`
+pub fn unpack(bundle: &[u8], dest: &Path) -> Result<BundleManifest, Box<dyn std::error::Error>> {
+    let mut zip = ZipArchive::new(Cursor::new(bundle)).map_err(|_| "Not a deployment bundle")?;
+
+    let manifest: BundleManifest = match zip.by_name(MANIFEST_FILENAME) {
+        Ok(reader) => serde_json::from_reader(reader).map_err(|e| format!("Invalid bundle manifest: {}", e))?,
+        Err(_) => return Err(format!("Not a deployment bundle, {} is missing", MANIFEST_FILENAME).into()),
+    };
+    if manifest.version != BUNDLE_VERSION {
+        return Err(format!(
+            "Unsupported bundle version {}, expected {}",
+            manifest.version, BUNDLE_VERSION
+        )
+        .into());
+    }
+    if !is_relative_path(&manifest.name) || manifest.name.contains('/') {
+        return Err(format!("Invalid deployment name {} in bundle", manifest.name).into());
+    }
+
+    for name in zip.file_names() {
+        if name != MANIFEST_FILENAME && !manifest.files.contains_key(name) {
+            return Err(format!("Bundle contains {} which is not in its manifest", name).into());
+        }
+    }
+
+    for (path, checksum) in &manifest.files {
+        if !is_relative_path(path) {
+            return Err(format!("Invalid path {} in bundle", path).into());
+        }
+
+        let mut content = vec![];
+        zip.by_name(path)
+            .map_err(|_| format!("Bundle is missing {}", path))?
+            .read_to_end(&mut content)?;
+        if &sha256(&content) != checksum {
+            return Err(format!("Checksum mismatch for {}, the bundle is corrupted", path).into());
+        }
+
+        let file_path = dest.join(path);
+        if let Some(parent) = file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(file_path, content)?;
+    }
+
+    Ok(manifest)
+}
+
+pub fn is_encrypted(bundle: &[u8]) -> bool {
+    bundle.starts_with(ENCRYPTED_MAGIC)
+}
+
+/// Encrypts the bundle with aes-256-gcm, the key is derived from `passphrase` with scrypt
+pub fn encrypt(bundle: &[u8], passphrase: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new_from_slice(&derive_key(passphrase, &salt)?)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), bundle)
+        .map_err(|_| "Failed to encrypt bundle")?;
+
+    Ok([ENCRYPTED_MAGIC, &salt, &nonce, &ciphertext].concat())
+}
+
+pub fn decrypt(bundle: &[u8], passphrase: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let header_len = ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if !is_encrypted(bundle) || bundle.len() < header_len {
+        return Err("Not an encrypted deployment bundle".into());
+    }
+
+    let salt = &bundle[ENCRYPTED_MAGIC.len()..ENCRYPTED_MAGIC.len() + SALT_LEN];
+    let nonce = &bundle[ENCRYPTED_MAGIC.len() + SALT_LEN..header_len];
+
+    let cipher = Aes256Gcm::new_from_slice(&derive_key(passphrase, salt)?)?;
+    let bundle = cipher
+        .decrypt(Nonce::from_slice(nonce), &bundle[header_len..])
+        .map_err(|_| "Failed to decrypt bundle, wrong passphrase or corrupted file")?;
+
+    Ok(bundle)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], Box<dyn std::error::Error>> {
+    let params = scrypt::Params::new(SCRYPT_LOG_N, 8, 1)?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)?;
+
+    Ok(key)
+}
+
+fn list_files(dir: &Path) -> Result<Vec<std::path::PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(list_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+// rejects absolute paths and parent references so a bundle can't write outside its destination
+fn is_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn sha256(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle_with(manifest: &BundleManifest, files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        for (path, content) in files {
+            zip.start_file(*path, FileOptions::default()).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.start_file(MANIFEST_FILENAME, FileOptions::default())
+            .unwrap();
+        zip.write_all(&serde_json::to_vec(manifest).unwrap())
+            .unwrap();
+
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn manifest(version: u32, files: &[(&str, &[u8])]) -> BundleManifest {
+        BundleManifest {
+            version,
+            name: "prod".to_string(),
+            created_at: 0,
+            files: files
+                .iter()
+                .map(|(path, content)| (path.to_string(), sha256(content)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn pack_and_unpack_round_trip() {
+        let src = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(src.path().join("config")).unwrap();
+        std::fs::write(src.path().join("config/network.json"), "{}").unwrap();
+
+        let bundle = pack(src.path(), "prod").unwrap();
+        let dest = tempfile::TempDir::new().unwrap();
+        let manifest = unpack(&bundle, dest.path()).unwrap();
+
+        assert_eq!(manifest.name, "prod");
+        assert_eq!(manifest.version, BUNDLE_VERSION);
+        assert_eq!(
+            std::fs::read_to_string(dest.path().join("config/network.json")).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn unpack_rejects_tampered_files() {
+        let manifest = manifest(BUNDLE_VERSION, &[("config/network.json", b"{}")]);
+        let bundle = bundle_with(
+            &manifest,
+            &[("config/network.json", b"{\"l1_chain_id\": 1}")],
+        );
+
+        let error = unpack(&bundle, tempfile::TempDir::new().unwrap().path())
+            .unwrap_err()
+            .to_string();
+
+        assert_eq!(
+            error,
+            "Checksum mismatch for config/network.json, the bundle is corrupted"
+        );
+    }
+
+    #[test]
+    fn unpack_rejects_unknown_versions_and_paths() {
+        let dest = tempfile::TempDir::new().unwrap();
+
+        let bundle = bundle_with(&manifest(BUNDLE_VERSION + 1, &[]), &[]);
+        assert!(unpack(&bundle, dest.path()).is_err());
+
+        let files: &[(&str, &[u8])] = &[("../network.json", b"{}")];
+        let bundle = bundle_with(&manifest(BUNDLE_VERSION, files), files);
+        assert!(unpack(&bundle, dest.path()).is_err());
+    }
+
+    #[test]
+    fn encrypt_and_decrypt_round_trip() {
+        let encrypted = encrypt(b"bundle", "passphrase").unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt(&encrypted, "passphrase").unwrap(), b"bundle");
+        assert!(decrypt(&encrypted, "wrong").is_err());
+    }
+}
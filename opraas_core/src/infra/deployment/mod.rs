@@ -1,3 +1,4 @@
+pub mod bundle;
 pub mod repo_inmemory;
 
 pub use repo_inmemory::*;
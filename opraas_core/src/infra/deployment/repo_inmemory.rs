@@ -1,22 +1,33 @@
+use super::bundle;
 use crate::{
     config::{AccountsConfig, NetworkConfig},
     domain::{self, Deployment},
     system,
 };
+use log::warn;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
+    collections::HashMap,
     fs::{File, OpenOptions},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 pub struct InMemoryDeploymentRepository {
     root: PathBuf,
+    secrets: SecretsLookup,
 }
 
+// secrets are never written to disk, they're looked up by env variable name on load
+type SecretsLookup = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct ReleaseMetadata {
     name: String,
     registry_url: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    overrides: HashMap<String, String>,
 }
 
 const NETWORK_FILENAME: &str = "config/network.json";
@@ -24,6 +35,7 @@ const ACCOUNTS_FILENAME: &str = "config/accounts.json";
 const RELEASE_FILENAME: &str = "config/release.json";
 const CONTRACTS_ARTIFACTS_FILENAME: &str = "artifacts/contracts_artifacts.zip";
 const INFRA_ARTIFACTS_FILENAME: &str = "artifacts/infra_artifacts.json";
+const ARCHIVE_DIRNAME: &str = ".archive";
 
 // implementations ====================================
 
@@ -34,12 +46,30 @@ impl InMemoryDeploymentRepository {
 
         Self {
             root: deployments_root,
+            secrets: Box::new(|var| std::env::var(var).ok()),
         }
     }
 
+    /// Fills the secret fields of a stored config, those missing from the lookup fall back to the config defaults
+    fn with_secrets(&self, mut value: Value, fields: &[(String, String)]) -> Value {
+        if let Value::Object(map) = &mut value {
+            for (field, var) in fields {
+                if let Some(secret) = (self.secrets)(var) {
+                    map.insert(field.clone(), Value::String(secret));
+                }
+            }
+        }
+
+        value
+    }
+
     fn load_network_config(&self, depl_path: &PathBuf) -> Result<NetworkConfig, Box<dyn std::error::Error>> {
         let reader = File::open(depl_path.join(NETWORK_FILENAME))?;
-        let config: NetworkConfig = serde_json::from_reader(reader)?;
+        let value = self.with_secrets(
+            serde_json::from_reader(reader)?,
+            &[("l1_rpc_url".to_string(), "L1_RPC_URL".to_string())],
+        );
+        let config: NetworkConfig = serde_json::from_value(value)?;
 
         Ok(config)
     }
@@ -61,7 +91,13 @@ impl InMemoryDeploymentRepository {
 
     fn load_accounts_config(&self, depl_path: &PathBuf) -> Result<AccountsConfig, Box<dyn std::error::Error>> {
         let reader = File::open(depl_path.join(ACCOUNTS_FILENAME))?;
-        let config: AccountsConfig = serde_json::from_reader(reader)?;
+        let fields: Vec<(String, String)> = AccountsConfig::ROLES
+            .iter()
+            .zip(AccountsConfig::ENV_VARS)
+            .map(|(role, var)| (format!("{}_private_key", role), var.to_string()))
+            .collect();
+        let value = self.with_secrets(serde_json::from_reader(reader)?, &fields);
+        let config: AccountsConfig = serde_json::from_value(value)?;
 
         Ok(config)
     }
@@ -139,6 +175,7 @@ impl domain::deployment::TDeploymentRepository for InMemoryDeploymentRepository
         Ok(Some(Deployment {
             name: name.to_string(),
             release_name: releases.name,
+            release_overrides: releases.overrides,
             registry_url: releases.registry_url,
             network_config,
             accounts_config,
@@ -160,6 +197,7 @@ impl domain::deployment::TDeploymentRepository for InMemoryDeploymentRepository
             &ReleaseMetadata {
                 name: deployment.release_name.clone(),
                 registry_url: deployment.registry_url.clone(),
+                overrides: deployment.release_overrides.clone(),
             },
         )?;
 
@@ -178,4 +216,266 @@ impl domain::deployment::TDeploymentRepository for InMemoryDeploymentRepository
 
         Ok(())
     }
+
+    fn list(&self) -> Result<Vec<Deployment>, Box<dyn std::error::Error>> {
+        let mut names: Vec<String> = vec![];
+        for entry in std::fs::read_dir(&self.root)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            // skip archived deployments and any other hidden folder
+            if entry.file_type()?.is_dir() && !name.starts_with('.') {
+                names.push(name);
+            }
+        }
+        names.sort();
+
+        let mut deployments = vec![];
+        for name in names {
+            // a broken folder shouldn't hide the rest of the deployments
+            match self.find(&name) {
+                Ok(Some(deployment)) => deployments.push(deployment),
+                Ok(None) => {}
+                Err(e) => warn!("Skipping deployment {}, it can't be loaded: {}", name, e),
+            }
+        }
+
+        Ok(deployments)
+    }
+
+    fn archive(&self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let depl_path = self.root.join(name);
+        if !std::fs::exists(&depl_path)? {
+            return Err(format!("Deployment {} not found", name).into());
+        }
+
+        // keep every archived copy around, suffixing them with the archival time
+        let archive_root = self.root.join(ARCHIVE_DIRNAME);
+        std::fs::create_dir_all(&archive_root)?;
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        std::fs::rename(
+            &depl_path,
+            archive_root.join(format!("{}-{}", name, timestamp)),
+        )?;
+
+        Ok(())
+    }
+
+    fn export(&self, name: &str, dest: &Path, passphrase: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let depl_path = self.root.join(name);
+        if !std::fs::exists(&depl_path)? {
+            return Err(format!("Deployment {} not found", name).into());
+        }
+
+        let mut bundle = bundle::pack(&depl_path, name)?;
+        if let Some(passphrase) = passphrase {
+            bundle = bundle::encrypt(&bundle, passphrase)?;
+        }
+        std::fs::write(dest, bundle)?;
+
+        Ok(())
+    }
+
+    fn import(
+        &self,
+        src: &Path,
+        passphrase: Option<&str>,
+        force: bool,
+    ) -> Result<Deployment, Box<dyn std::error::Error>> {
+        let mut bundle = std::fs::read(src)?;
+        if bundle::is_encrypted(&bundle) {
+            let passphrase = passphrase.ok_or("Bundle is encrypted, a passphrase is required")?;
+            bundle = bundle::decrypt(&bundle, passphrase)?;
+        }
+
+        // unpack into a hidden folder first so a broken bundle never touches existing deployments
+        let staging = tempfile::Builder::new().prefix(".import-").tempdir_in(&self.root)?;
+        let manifest = bundle::unpack(&bundle, staging.path())?;
+
+        let depl_path = self.root.join(&manifest.name);
+        if std::fs::exists(&depl_path)? {
+            if !force {
+                return Err(format!("Deployment {} already exists, use --force to replace it", manifest.name).into());
+            }
+            self.archive(&manifest.name)?;
+        }
+        std::fs::rename(staging.into_path(), &depl_path)?;
+
+        self.find(&manifest.name)?
+            .ok_or_else(|| format!("Deployment {} not found after import", manifest.name).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::TDeploymentRepository;
+
+    // secrets come from a fixed lookup so the tests never touch the process env
+    fn new_repository(root: &Path) -> InMemoryDeploymentRepository {
+        let deployments_root = root.join("deployments");
+        std::fs::create_dir_all(&deployments_root).unwrap();
+
+        InMemoryDeploymentRepository {
+            root: deployments_root,
+            secrets: Box::new(|var| match var {
+                "L1_RPC_URL" => Some("http://localhost:8545".to_string()),
+                _ => Some("0x00".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn archive_moves_deployment_out_of_the_way() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(root.path());
+
+        let mut deployment = Deployment::new(
+            "my-deployment".to_string(),
+            "v0.0.1".to_string(),
+            "wakeuplabs".to_string(),
+            NetworkConfig::null(),
+            AccountsConfig::null(),
+        );
+        repository.save(&mut deployment).unwrap();
+
+        repository.archive("my-deployment").unwrap();
+
+        assert!(repository.find("my-deployment").unwrap().is_none());
+        assert_eq!(
+            std::fs::read_dir(root.path().join("deployments").join(ARCHIVE_DIRNAME))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn archive_fails_if_deployment_does_not_exist() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(root.path());
+
+        assert!(repository.archive("my-deployment").is_err());
+    }
+
+    #[test]
+    fn release_overrides_round_trip() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(root.path());
+        let depl_path = root.path().join("deployments").join("my-deployment");
+        std::fs::create_dir_all(depl_path.join("config")).unwrap();
+
+        repository
+            .write_releases_config(
+                &depl_path,
+                &ReleaseMetadata {
+                    name: "v0.0.1".to_string(),
+                    registry_url: "wakeuplabs".to_string(),
+                    overrides: HashMap::from([("op-node".to_string(), "v0.0.2".to_string())]),
+                },
+            )
+            .unwrap();
+
+        let releases = repository.load_releases_config(&depl_path).unwrap();
+        assert_eq!(releases.name, "v0.0.1");
+        assert_eq!(releases.overrides.get("op-node").unwrap(), "v0.0.2");
+    }
+
+    #[test]
+    fn release_overrides_are_optional() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(root.path());
+        let depl_path = root.path().join("deployments").join("my-deployment");
+        std::fs::create_dir_all(depl_path.join("config")).unwrap();
+        std::fs::write(
+            depl_path.join(RELEASE_FILENAME),
+            r#"{"name": "v0.0.1", "registry_url": "wakeuplabs"}"#,
+        )
+        .unwrap();
+
+        let releases = repository.load_releases_config(&depl_path).unwrap();
+        assert!(releases.overrides.is_empty());
+    }
+
+    #[test]
+    fn list_returns_deployments_sorted_by_name() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(root.path());
+
+        for name in ["prod", "dev", "archived"] {
+            let mut deployment = Deployment::new(
+                name.to_string(),
+                "v0.0.1".to_string(),
+                "wakeuplabs".to_string(),
+                NetworkConfig::null(),
+                AccountsConfig::null(),
+            );
+            repository.save(&mut deployment).unwrap();
+        }
+        repository.archive("archived").unwrap();
+        std::fs::create_dir_all(root.path().join("broken")).unwrap();
+
+        let deployments = repository.list().unwrap();
+        let names: Vec<&str> = deployments.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["dev", "prod"]);
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let src = tempfile::TempDir::new().unwrap();
+        let src_repository = new_repository(src.path());
+        let mut deployment = Deployment::new(
+            "prod".to_string(),
+            "v0.0.1".to_string(),
+            "wakeuplabs".to_string(),
+            NetworkConfig::null(),
+            AccountsConfig::null(),
+        );
+        src_repository.save(&mut deployment).unwrap();
+
+        let bundle = src.path().join("prod.opruaas");
+        src_repository
+            .export("prod", &bundle, Some("passphrase"))
+            .unwrap();
+
+        let dest = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(dest.path());
+        assert!(repository.import(&bundle, None, false).is_err());
+
+        let imported = repository
+            .import(&bundle, Some("passphrase"), false)
+            .unwrap();
+        assert_eq!(imported.name, "prod");
+        assert_eq!(imported.release_name, "v0.0.1");
+        assert_eq!(repository.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn import_refuses_to_overwrite_unless_forced() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = new_repository(root.path());
+        let mut deployment = Deployment::new(
+            "prod".to_string(),
+            "v0.0.1".to_string(),
+            "wakeuplabs".to_string(),
+            NetworkConfig::null(),
+            AccountsConfig::null(),
+        );
+        repository.save(&mut deployment).unwrap();
+
+        let bundle = root.path().join("prod.opruaas");
+        repository.export("prod", &bundle, None).unwrap();
+
+        let error = repository.import(&bundle, None, false).unwrap_err();
+        assert_eq!(error.to_string(), "Deployment prod already exists, use --force to replace it");
+
+        repository.import(&bundle, None, true).unwrap();
+        assert_eq!(
+            std::fs::read_dir(root.path().join("deployments").join(ARCHIVE_DIRNAME))
+                .unwrap()
+                .count(),
+            1
+        );
+    }
 }
@@ -0,0 +1,3 @@
+pub mod repo_inmemory;
+
+pub use repo_inmemory::*;
@@ -0,0 +1,76 @@
+use crate::domain::{DevnetState, TDevnetStateRepository};
+use std::{fs::File, path::PathBuf};
+
+pub struct InMemoryDevnetStateRepository {
+    path: PathBuf,
+}
+
+// local state that shouldn't be committed, the new command gitignores it
+const STATE_DIRNAME: &str = ".opruaas";
+const DEVNET_STATE_FILENAME: &str = "devnet.json";
+
+// implementations ====================================
+
+impl InMemoryDevnetStateRepository {
+    pub fn new(root: &std::path::Path) -> Self {
+        Self {
+            path: root.join(STATE_DIRNAME).join(DEVNET_STATE_FILENAME),
+        }
+    }
+}
+
+impl TDevnetStateRepository for InMemoryDevnetStateRepository {
+    fn save(&self, state: &DevnetState) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(self.path.parent().unwrap())?;
+        serde_json::to_writer_pretty(File::create(&self.path)?, state)?;
+
+        Ok(())
+    }
+
+    fn find(&self) -> Result<Option<DevnetState>, Box<dyn std::error::Error>> {
+        if !std::fs::exists(&self.path)? {
+            return Ok(None);
+        }
+
+        let state: DevnetState = serde_json::from_reader(File::open(&self.path)?)?;
+
+        Ok(Some(state))
+    }
+
+    fn delete(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if std::fs::exists(&self.path)? {
+            std::fs::remove_file(&self.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_round_trip() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = InMemoryDevnetStateRepository::new(root.path());
+        let state = DevnetState {
+            l1_node: "geth".to_string(),
+            l1_container: "geth-testnet-node".to_string(),
+            l1_port: 8545,
+            helm_release: "op-ruaas-runner-opruaas-dev".to_string(),
+            namespace: "opruaas-dev".to_string(),
+            deployment_name: "dev".to_string(),
+            started_at: 1700000000,
+        };
+
+        assert!(repository.find().unwrap().is_none());
+
+        repository.save(&state).unwrap();
+        assert_eq!(repository.find().unwrap(), Some(state));
+
+        repository.delete().unwrap();
+        assert!(repository.find().unwrap().is_none());
+        repository.delete().unwrap();
+    }
+}
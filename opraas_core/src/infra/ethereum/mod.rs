@@ -4,6 +4,11 @@ pub use rpc::*;
 pub use rpc_json::*;
 
 pub mod node;
+pub mod node_anvil;
 pub mod node_geth;
 pub use node::*;
+pub use node_anvil::*;
 pub use node_geth::*;
+
+pub mod wallet;
+pub use wallet::*;
@@ -0,0 +1,183 @@
+use super::{EthRpc, JsonRpc, TTestnetNode};
+use crate::system::execute_command;
+use std::{path::PathBuf, process::Command, thread, time};
+
+const DOCKER_IMAGE: &str = "ghcr.io/foundry-rs/foundry:v1.0.0";
+const MAX_TIMEOUT: u64 = 30;
+// where the state file's folder is mounted inside the container
+const STATE_MOUNT: &str = "/state";
+
+pub const ANVIL_CONTAINER_NAME: &str = "anvil-testnet-node";
+
+#[derive(Debug, Clone)]
+pub struct AnvilOptions {
+    pub container_name: String,
+    /// seconds between blocks
+    pub block_time: u64,
+    /// pre-funded accounts, derived from the "test test ... junk" mnemonic like hardhat's
+    pub accounts: u32,
+    /// ether each account starts with
+    pub balance: u64,
+    /// chain state is loaded from this file on start, when it exists, and dumped to it on stop
+    pub state: Option<PathBuf>,
+}
+
+pub struct AnvilTestnetNode {
+    eth_rpc: Box<dyn EthRpc>,
+    options: AnvilOptions,
+}
+
+// implementations ==============================================
+
+impl Default for AnvilOptions {
+    fn default() -> Self {
+        Self {
+            container_name: ANVIL_CONTAINER_NAME.to_string(),
+            block_time: 1,
+            accounts: 30,
+            balance: 10000,
+            state: None,
+        }
+    }
+}
+
+impl AnvilTestnetNode {
+    pub fn new(options: AnvilOptions) -> Self {
+        Self {
+            eth_rpc: Box::new(JsonRpc::new()),
+            options,
+        }
+    }
+
+    fn anvil_command(&self, chain_id: u32, port: u64) -> String {
+        let mut command = format!(
+            "exec anvil --host 0.0.0.0 --port {} --chain-id {} --block-time {} --accounts {} --balance {}",
+            port, chain_id, self.options.block_time, self.options.accounts, self.options.balance
+        );
+
+        if let Some(file_name) = self
+            .options
+            .state
+            .as_ref()
+            .and_then(|state| state.file_name())
+        {
+            command.push_str(&format!(
+                " --state {}/{}",
+                STATE_MOUNT,
+                file_name.to_string_lossy()
+            ));
+        }
+
+        command
+    }
+}
+
+impl TTestnetNode for AnvilTestnetNode {
+    fn start(&self, chain_id: u32, port: u64) -> Result<(), Box<dyn std::error::Error>> {
+        execute_command(Command::new("docker").args(["pull", DOCKER_IMAGE]), false)?;
+
+        let mut command = Command::new("docker");
+        command
+            .args(["run", "-d", "--rm"])
+            .args(["-p", &format!("{}:{}", port, port)])
+            .args(["--name", &self.options.container_name]);
+
+        if let Some(state) = &self.options.state {
+            let state_dir = match state.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            std::fs::create_dir_all(&state_dir)?;
+
+            command.args([
+                "-v",
+                &format!(
+                    "{}:{}",
+                    std::fs::canonicalize(&state_dir)?.display(),
+                    STATE_MOUNT
+                ),
+            ]);
+        }
+
+        // the foundry image entrypoint is `sh -c`, exec so anvil gets the stop signal and dumps its state
+        command
+            .arg(DOCKER_IMAGE)
+            .arg(self.anvil_command(chain_id, port));
+        execute_command(&mut command, true)?;
+
+        // wait for node to start
+        let timeout_duration = time::Duration::from_secs(MAX_TIMEOUT);
+        let start_time = time::Instant::now();
+        let rpc_url = format!("http://127.0.0.1:{}", port);
+
+        loop {
+            if start_time.elapsed() >= timeout_duration {
+                return Err(format!(
+                    "Timeout reached: Node did not respond within {} seconds.",
+                    MAX_TIMEOUT
+                )
+                .into());
+            }
+
+            if self
+                .eth_rpc
+                .send_rpc_request(&rpc_url, 1, "eth_chainId", vec![])
+                .is_ok()
+            {
+                break;
+            }
+
+            thread::sleep(time::Duration::from_secs(2));
+        }
+
+        Ok(())
+    }
+
+    fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let running_containers = execute_command(Command::new("docker").arg("ps"), true)?;
+        if !running_containers.contains(&self.options.container_name) {
+            return Ok(());
+        }
+
+        // give anvil time to dump its state before docker kills it
+        let _ = execute_command(
+            Command::new("docker")
+                .args(["stop", "--time", "30"])
+                .arg(&self.options.container_name),
+            true,
+        );
+        let _ = execute_command(
+            Command::new("docker")
+                .arg("rm")
+                .arg(&self.options.container_name),
+            true,
+        );
+
+        Ok(())
+    }
+
+    fn container_name(&self) -> &str {
+        &self.options.container_name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_pass_options_to_anvil() {
+        let node = AnvilTestnetNode::new(AnvilOptions {
+            block_time: 2,
+            accounts: 5,
+            balance: 100,
+            state: Some(PathBuf::from(".opruaas/anvil-state.json")),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            node.anvil_command(11155111, 8545),
+            "exec anvil --host 0.0.0.0 --port 8545 --chain-id 11155111 --block-time 2 --accounts 5 --balance 100 --state /state/anvil-state.json"
+        );
+    }
+}
@@ -156,4 +156,7 @@ impl TTestnetNode for GethTestnetNode {
 
         Ok(())
     }
+    fn container_name(&self) -> &str {
+        CONTAINER_NAME
+    }
 }
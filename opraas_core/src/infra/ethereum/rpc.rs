@@ -1,7 +1,9 @@
+use mockall::automock;
 use serde_json::Value;
 use std::error::Error;
 
-pub trait EthRpc {
+#[automock]
+pub trait EthRpc: Send + Sync {
     fn send_rpc_request(
         &self,
         base_url: &str,
@@ -0,0 +1,166 @@
+use crate::config::to_checksum_address;
+use bip32::{DerivationPath, XPrv};
+use bip39::Mnemonic;
+use k256::ecdsa::SigningKey;
+use rand::{rngs::OsRng, RngCore};
+use sha3::{Digest, Keccak256};
+use std::path::{Path, PathBuf};
+
+/// A secp256k1 key pair and its ethereum address
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wallet {
+    pub address: String,
+    pub private_key: String,
+}
+
+// ethereum bip44 path, the last index is appended per account
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0";
+
+// implementations ===================================================
+
+impl Wallet {
+    pub fn random() -> Self {
+        Self::from_signing_key(&SigningKey::random(&mut OsRng))
+    }
+
+    pub fn from_private_key(private_key: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let hex = private_key.trim().trim_start_matches("0x");
+        if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("Private key must be a 32 bytes hex".into());
+        }
+
+        let bytes: Vec<u8> = (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<_, _>>()?;
+        let signing_key = SigningKey::from_slice(&bytes).map_err(|_| "Private key is not a valid secp256k1 key")?;
+
+        Ok(Self::from_signing_key(&signing_key))
+    }
+
+    /// Derives the wallet at `path` (e.g. m/44'/60'/0'/0/0) from a bip39 english mnemonic
+    pub fn from_mnemonic(mnemonic: &str, path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mnemonic = Mnemonic::parse_normalized(mnemonic.trim()).map_err(|e| format!("Invalid mnemonic: {}", e))?;
+        let path: DerivationPath = path
+            .parse()
+            .map_err(|_| format!("Invalid derivation path {}", path))?;
+        let xprv = XPrv::derive_from_path(mnemonic.to_seed(""), &path)?;
+
+        Ok(Self::from_signing_key(xprv.private_key()))
+    }
+
+    /// New random 24 words bip39 english mnemonic
+    pub fn generate_mnemonic() -> String {
+        let mut entropy = [0u8; 32];
+        OsRng.fill_bytes(&mut entropy);
+
+        Mnemonic::from_entropy(&entropy).unwrap().to_string()
+    }
+
+    /// Writes the key as a web3 secret storage (v3) keystore named `name` inside `dir`
+    pub fn encrypt(&self, dir: &Path, password: &str, name: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let signing_key = SigningKey::from_slice(&self.private_key_bytes()?)?;
+
+        std::fs::create_dir_all(dir)?;
+        eth_keystore::encrypt_key(
+            dir,
+            &mut OsRng,
+            signing_key.to_bytes(),
+            password,
+            Some(name),
+        )?;
+
+        Ok(dir.join(name))
+    }
+
+    /// Reads back a keystore written by `encrypt`
+    pub fn decrypt(path: &Path, password: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let bytes = eth_keystore::decrypt_key(path, password)
+            .map_err(|e| format!("Failed to decrypt {}: {}", path.display(), e))?;
+        let signing_key = SigningKey::from_slice(&bytes)?;
+
+        Ok(Self::from_signing_key(&signing_key))
+    }
+
+    fn from_signing_key(signing_key: &SigningKey) -> Self {
+        // address is the last 20 bytes of the keccak of the uncompressed public key, without its 0x04 prefix
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+
+        Self {
+            address: to_checksum_address(&to_hex(&hash[12..])),
+            private_key: format!("0x{}", to_hex(&signing_key.to_bytes())),
+        }
+    }
+
+    fn private_key_bytes(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let hex = self.private_key.trim_start_matches("0x");
+
+        Ok((0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Wallet, DEFAULT_DERIVATION_PATH};
+
+    // well known hardhat/anvil test mnemonic and its first account
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+    const TEST_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const TEST_PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[test]
+    fn should_derive_address_from_private_key() {
+        let wallet = Wallet::from_private_key(TEST_PRIVATE_KEY).unwrap();
+
+        assert_eq!(wallet.address, TEST_ADDRESS);
+        assert_eq!(wallet.private_key, TEST_PRIVATE_KEY);
+    }
+
+    #[test]
+    fn should_derive_wallet_from_mnemonic() {
+        let wallet = Wallet::from_mnemonic(TEST_MNEMONIC, &format!("{}/0", DEFAULT_DERIVATION_PATH)).unwrap();
+
+        assert_eq!(wallet.address, TEST_ADDRESS);
+        assert_eq!(wallet.private_key, TEST_PRIVATE_KEY);
+    }
+
+    #[test]
+    fn should_generate_valid_mnemonics() {
+        let mnemonic = Wallet::generate_mnemonic();
+
+        assert_eq!(mnemonic.split(' ').count(), 24);
+        assert!(Wallet::from_mnemonic(&mnemonic, &format!("{}/0", DEFAULT_DERIVATION_PATH)).is_ok());
+    }
+
+    #[test]
+    fn should_reject_invalid_private_keys() {
+        assert!(Wallet::from_private_key("0x1234").is_err());
+        assert!(Wallet::from_private_key(&format!("0x{}", "0".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn should_encrypt_to_keystore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let wallet = Wallet::random();
+
+        let keystore = wallet
+            .encrypt(dir.path(), "password", "admin.json")
+            .unwrap();
+        let decrypted = eth_keystore::decrypt_key(&keystore, "password").unwrap();
+
+        assert_eq!(
+            Wallet::from_private_key(&super::to_hex(&decrypted)).unwrap(),
+            wallet
+        );
+        assert_eq!(Wallet::decrypt(&keystore, "password").unwrap(), wallet);
+        assert!(Wallet::decrypt(&keystore, "wrong").is_err());
+    }
+}
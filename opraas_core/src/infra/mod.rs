@@ -1,5 +1,6 @@
 pub mod artifact;
 pub mod deployment;
+pub mod devnet;
 pub mod ethereum;
 pub mod project;
 pub mod release;
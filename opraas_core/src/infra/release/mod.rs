@@ -1,5 +1,11 @@
+pub mod registry_local;
+pub mod registry_oci;
 pub mod repo_docker;
+pub mod repo_manifest;
 pub mod runner_docker;
 
+pub use registry_local::*;
+pub use registry_oci::*;
 pub use repo_docker::*;
+pub use repo_manifest::*;
 pub use runner_docker::*;
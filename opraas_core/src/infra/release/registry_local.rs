@@ -0,0 +1,99 @@
+use crate::system::execute_command;
+use std::{process::Command, thread, time};
+
+const DOCKER_IMAGE: &str = "registry:2";
+const MAX_TIMEOUT: u64 = 30;
+
+pub const LOCAL_REGISTRY_CONTAINER_NAME: &str = "opruaas-local-registry";
+
+/// Throwaway registry to release locally built images to, without pushing them anywhere public
+pub trait TLocalRegistry: Send + Sync {
+    /// Starts the registry unless it's already running and returns its url
+    fn start(&self) -> Result<String, Box<dyn std::error::Error>>;
+    fn stop(&self) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+pub struct DockerLocalRegistry {
+    port: u64,
+}
+
+// implementations ==============================================
+
+impl DockerLocalRegistry {
+    pub fn new(port: u64) -> Self {
+        Self { port }
+    }
+
+    // docker and docker desktop's kubernetes trust localhost registries without tls
+    fn url(&self) -> String {
+        format!("localhost:{}", self.port)
+    }
+
+    fn is_running(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        let running_containers = execute_command(Command::new("docker").arg("ps"), true)?;
+
+        Ok(running_containers.contains(LOCAL_REGISTRY_CONTAINER_NAME))
+    }
+}
+
+impl TLocalRegistry for DockerLocalRegistry {
+    fn start(&self) -> Result<String, Box<dyn std::error::Error>> {
+        if self.is_running()? {
+            return Ok(self.url());
+        }
+
+        execute_command(Command::new("docker").args(["pull", DOCKER_IMAGE]), false)?;
+        execute_command(
+            Command::new("docker").args([
+                "run",
+                "-d",
+                "--rm",
+                "-p",
+                &format!("{}:5000", self.port),
+                "--name",
+                LOCAL_REGISTRY_CONTAINER_NAME,
+                DOCKER_IMAGE,
+            ]),
+            true,
+        )?;
+
+        // wait for registry to accept requests
+        let timeout_duration = time::Duration::from_secs(MAX_TIMEOUT);
+        let start_time = time::Instant::now();
+        let api_url = format!("http://{}/v2/", self.url());
+
+        loop {
+            if start_time.elapsed() >= timeout_duration {
+                return Err(format!(
+                    "Timeout reached: Local registry did not respond within {} seconds.",
+                    MAX_TIMEOUT
+                )
+                .into());
+            }
+
+            if reqwest::blocking::get(&api_url).is_ok_and(|response| response.status().is_success()) {
+                break;
+            }
+
+            thread::sleep(time::Duration::from_secs(1));
+        }
+
+        Ok(self.url())
+    }
+
+    fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.is_running()? {
+            return Ok(());
+        }
+
+        // started with --rm, stopping also removes it along with the pushed images
+        execute_command(
+            Command::new("docker")
+                .arg("stop")
+                .arg(LOCAL_REGISTRY_CONTAINER_NAME),
+            true,
+        )?;
+
+        Ok(())
+    }
+}
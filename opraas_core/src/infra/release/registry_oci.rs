@@ -0,0 +1,451 @@
+use crate::domain::{Release, TReleaseRegistry};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{
+    blocking::{Client, Response},
+    header::{HeaderMap, ACCEPT, AUTHORIZATION, LINK, WWW_AUTHENTICATE},
+    Method, StatusCode, Url,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+const DOCKER_HUB_HOST: &str = "registry-1.docker.io";
+// docker login keeps the docker hub credentials under its legacy index url
+const DOCKER_HUB_CREDENTIALS_KEY: &str = "https://index.docker.io/v1/";
+
+// single platform images and manifest lists, in both docker and oci flavours
+const MANIFEST_MEDIA_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// Reads releases straight from a registry through the OCI distribution api, with the credentials of `docker login`
+/// when the registry asks for them
+pub struct OciReleaseRegistry {
+    client: Client,
+}
+
+#[derive(Deserialize)]
+struct TagList {
+    // registries answer null for repositories without tags
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+// the parts of ~/.docker/config.json that say where credentials are
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, DockerAuth>,
+    creds_store: Option<String>,
+    #[serde(default)]
+    cred_helpers: HashMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct DockerAuth {
+    // base64 of user:password
+    auth: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HelperCredentials {
+    username: String,
+    secret: String,
+}
+
+// implementations ==============================================
+
+impl Default for OciReleaseRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OciReleaseRegistry {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+        }
+    }
+
+    // registries that require auth answer 401 saying how to authenticate, with a token for the repository or basic
+    // auth. `authorization` keeps what worked for the next requests.
+    fn send(
+        &self,
+        method: Method,
+        url: &str,
+        accept: &str,
+        authorization: &mut Option<String>,
+    ) -> Result<Response, Box<dyn std::error::Error>> {
+        let send = |authorization: &Option<String>| {
+            let request = self
+                .client
+                .request(method.clone(), url)
+                .header(ACCEPT, accept);
+            match authorization {
+                Some(authorization) => request.header(AUTHORIZATION, authorization).send(),
+                None => request.send(),
+            }
+        };
+
+        let response = send(authorization)?;
+        if response.status() != StatusCode::UNAUTHORIZED || authorization.is_some() {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(WWW_AUTHENTICATE)
+            .and_then(|header| header.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let host = Url::parse(url)?
+            .host_str()
+            .map(|host| host.to_string())
+            .unwrap_or_default();
+        let credentials = docker_config_dir().and_then(|config_dir| docker_credentials(&config_dir, &host));
+
+        *authorization = match bearer_challenge(&challenge) {
+            Some((realm, query)) => {
+                let mut request = self.client.get(Url::parse_with_params(&realm, &query)?);
+                if let Some(credentials) = &credentials {
+                    request = request.header(AUTHORIZATION, format!("Basic {}", credentials));
+                }
+                let token_response = request.send()?;
+                if !token_response.status().is_success() {
+                    return Err(format!(
+                        "{} answered {} authenticating to {}, check docker login",
+                        realm,
+                        token_response.status(),
+                        host
+                    )
+                    .into());
+                }
+
+                let token_response: TokenResponse = serde_json::from_str(&token_response.text()?)?;
+                token_response
+                    .token
+                    .or(token_response.access_token)
+                    .map(|token| format!("Bearer {}", token))
+            }
+            None => credentials.map(|credentials| format!("Basic {}", credentials)),
+        };
+        if authorization.is_none() {
+            return Err(format!(
+                "{} requires credentials, docker login to {} first",
+                url, host
+            )
+            .into());
+        }
+
+        Ok(send(authorization)?)
+    }
+}
+
+impl TReleaseRegistry for OciReleaseRegistry {
+    fn tags(&self, registry_url: &str, artifact_name: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let (base_url, repository) = repository_url(registry_url, artifact_name);
+        let mut authorization = None;
+
+        let mut tags = vec![];
+        let mut url = format!("{}/v2/{}/tags/list", base_url, repository);
+        loop {
+            let response = self.send(Method::GET, &url, "application/json", &mut authorization)?;
+            match response.status() {
+                // nothing was ever pushed for the artifact, docker hub denies repositories that don't exist
+                StatusCode::NOT_FOUND => return Ok(vec![]),
+                StatusCode::UNAUTHORIZED if is_docker_hub(&base_url) => return Ok(vec![]),
+                status if !status.is_success() => {
+                    return Err(format!(
+                        "{} answered {} listing {} tags",
+                        base_url, status, repository
+                    )
+                    .into())
+                }
+                _ => {}
+            }
+
+            let next = next_page(response.headers());
+            let tag_list: TagList = serde_json::from_str(&response.text()?)?;
+            tags.extend(tag_list.tags.unwrap_or_default());
+
+            match next {
+                Some(next) => url = format!("{}{}", base_url, next),
+                None => break,
+            }
+        }
+
+        Ok(tags)
+    }
+
+    fn exists(&self, release: &Release) -> Result<bool, Box<dyn std::error::Error>> {
+        let (base_url, repository) = repository_url(&release.registry_url, &release.artifact_name);
+        let reference = release.digest.as_ref().unwrap_or(&release.artifact_tag);
+
+        let url = format!("{}/v2/{}/manifests/{}", base_url, repository, reference);
+        let response = self.send(Method::HEAD, &url, MANIFEST_MEDIA_TYPES, &mut None)?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            StatusCode::UNAUTHORIZED if is_docker_hub(&base_url) => Ok(false),
+            status => Err(format!(
+                "{} answered {} looking up {}",
+                base_url,
+                status,
+                release.pinned_uri()
+            )
+            .into()),
+        }
+    }
+}
+
+// splits registry urls like localhost:5000, ghcr.io/acme or wakeuplabs (docker hub) into the api base url and the
+// artifact repository
+fn repository_url(registry_url: &str, artifact_name: &str) -> (String, String) {
+    let (scheme, registry_url) = match registry_url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, registry_url),
+    };
+    let registry_url = registry_url.trim_end_matches('/');
+
+    let (host, namespace) = match registry_url.split_once('/') {
+        Some((host, namespace)) if is_host(host) => (host, Some(namespace)),
+        None if is_host(registry_url) => (registry_url, None),
+        _ => (DOCKER_HUB_HOST, Some(registry_url)),
+    };
+    let host = match host {
+        "docker.io" | "index.docker.io" => DOCKER_HUB_HOST,
+        host => host,
+    };
+
+    // registries on this machine are usually plain http
+    let scheme = scheme.unwrap_or(
+        match host.starts_with("localhost") || host.starts_with("127.0.0.1") {
+            true => "http",
+            false => "https",
+        },
+    );
+    let repository = match namespace {
+        Some(namespace) => format!("{}/{}", namespace, artifact_name),
+        None => artifact_name.to_string(),
+    };
+
+    (format!("{}://{}", scheme, host), repository)
+}
+
+fn is_host(segment: &str) -> bool {
+    segment.contains('.') || segment.contains(':') || segment == "localhost"
+}
+
+fn is_docker_hub(base_url: &str) -> bool {
+    base_url == format!("https://{}", DOCKER_HUB_HOST)
+}
+
+fn docker_config_dir() -> Option<PathBuf> {
+    match env::var("DOCKER_CONFIG") {
+        Ok(config_dir) => Some(PathBuf::from(config_dir)),
+        Err(_) => env::var("HOME")
+            .ok()
+            .map(|home| Path::new(&home).join(".docker")),
+    }
+}
+
+// credentials `docker login` stored for the registry host as base64 of user:password, through a credential helper
+// when one is configured
+fn docker_credentials(config_dir: &Path, host: &str) -> Option<String> {
+    let config: DockerConfig = serde_json::from_str(&fs::read_to_string(config_dir.join("config.json")).ok()?).ok()?;
+    let key = match host {
+        DOCKER_HUB_HOST => DOCKER_HUB_CREDENTIALS_KEY,
+        host => host,
+    };
+
+    if let Some(helper) = config
+        .cred_helpers
+        .iter()
+        .find(|(server, _)| same_server(server, key))
+        .map(|(_, helper)| helper)
+        .or(config.creds_store.as_ref())
+    {
+        return helper_credentials(helper, key);
+    }
+
+    config
+        .auths
+        .into_iter()
+        .find(|(server, _)| same_server(server, key))
+        .and_then(|(_, auth)| auth.auth)
+}
+
+// docker-credential-<helper> get reads the server from stdin and answers json
+fn helper_credentials(helper: &str, server: &str) -> Option<String> {
+    let mut child = Command::new(format!("docker-credential-{}", helper))
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(server.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let credentials: HelperCredentials = serde_json::from_slice(&output.stdout).ok()?;
+    Some(STANDARD.encode(format!("{}:{}", credentials.username, credentials.secret)))
+}
+
+// config keys are hosts, optionally with scheme and path like https://index.docker.io/v1/
+fn same_server(a: &str, b: &str) -> bool {
+    let strip = |server: &str| {
+        server
+            .split_once("://")
+            .map_or(server, |(_, rest)| rest)
+            .trim_end_matches('/')
+            .to_string()
+    };
+
+    strip(a) == strip(b)
+}
+
+// Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:a/b:pull"
+fn bearer_challenge(header: &str) -> Option<(String, Vec<(String, String)>)> {
+    let params = header.strip_prefix("Bearer ")?;
+
+    // values are quoted and may have commas of their own, like scope="repository:a/b:pull,push"
+    let mut pairs = vec![];
+    let mut quoted = false;
+    let mut start = 0;
+    for (i, c) in params.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                pairs.push(&params[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    pairs.push(&params[start..]);
+
+    let mut realm = None;
+    let mut query = vec![];
+    for pair in pairs {
+        let (key, value) = pair.trim().split_once('=')?;
+        let value = value.trim_matches('"').to_string();
+        match key {
+            "realm" => realm = Some(value),
+            key => query.push((key.to_string(), value)),
+        }
+    }
+
+    Some((realm?, query))
+}
+
+// paginated responses link the next page like </v2/a/b/tags/list?last=v1&n=100>; rel="next"
+fn next_page(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    if !link.contains("rel=\"next\"") {
+        return None;
+    }
+
+    let start = link.find('<')? + 1;
+    let end = link.find('>')?;
+
+    Some(link[start..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_resolve_registry_urls() {
+        assert_eq!(
+            repository_url("localhost:5000", "op-node"),
+            ("http://localhost:5000".to_string(), "op-node".to_string())
+        );
+        assert_eq!(
+            repository_url("ghcr.io/acme", "op-node"),
+            ("https://ghcr.io".to_string(), "acme/op-node".to_string())
+        );
+        assert_eq!(
+            repository_url("wakeuplabs", "op-node"),
+            (
+                "https://registry-1.docker.io".to_string(),
+                "wakeuplabs/op-node".to_string()
+            )
+        );
+        assert_eq!(
+            repository_url("docker.io/wakeuplabs", "op-node"),
+            (
+                "https://registry-1.docker.io".to_string(),
+                "wakeuplabs/op-node".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn should_parse_bearer_challenges() {
+        let (realm, query) = bearer_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:wakeuplabs/op-node:pull,push""#,
+        )
+        .unwrap();
+
+        assert_eq!(realm, "https://auth.docker.io/token");
+        assert_eq!(
+            query,
+            vec![
+                ("service".to_string(), "registry.docker.io".to_string()),
+                (
+                    "scope".to_string(),
+                    "repository:wakeuplabs/op-node:pull,push".to_string()
+                ),
+            ]
+        );
+        assert!(bearer_challenge("Basic realm=\"registry\"").is_none());
+    }
+
+    #[test]
+    fn should_find_docker_login_credentials() {
+        let config_dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            config_dir.path().join("config.json"),
+            r#"{
+                "auths": {
+                    "https://index.docker.io/v1/": { "auth": "aHViOnNlY3JldA==" },
+                    "https://ghcr.io": { "auth": "Z2hjcjpzZWNyZXQ=" },
+                    "quay.io": {}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            docker_credentials(config_dir.path(), DOCKER_HUB_HOST),
+            Some("aHViOnNlY3JldA==".to_string())
+        );
+        assert_eq!(
+            docker_credentials(config_dir.path(), "ghcr.io"),
+            Some("Z2hjcjpzZWNyZXQ=".to_string())
+        );
+        assert_eq!(docker_credentials(config_dir.path(), "quay.io"), None);
+        assert_eq!(
+            docker_credentials(config_dir.path(), "localhost:5000"),
+            None
+        );
+    }
+}
@@ -1,16 +1,68 @@
+use super::InMemoryReleaseManifestRepository;
 use crate::{
-    domain::{self, Release},
-    system,
+    docker,
+    domain::{self, Release, ReleaseManifest, ReleasedArtifact, TReleaseManifestRepository},
+    git, system,
+};
+use std::{
+    path::Path,
+    process::Command,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
 };
-use std::process::Command;
 
-pub struct DockerReleaseRepository;
+pub struct DockerReleaseRepository {
+    manifest_repository: InMemoryReleaseManifestRepository,
+    // artifacts of a release are pushed concurrently and share its manifest
+    manifest_lock: Mutex<()>,
+}
 
 // implementations ==================================================
 
 impl DockerReleaseRepository {
-    pub fn new() -> Self {
-        Self
+    /// Records what's released in the manifests at `releases_dir`, the project's `releases`
+    pub fn new(releases_dir: &Path) -> Self {
+        Self {
+            manifest_repository: InMemoryReleaseManifestRepository::new(releases_dir),
+            manifest_lock: Mutex::new(()),
+        }
+    }
+
+    fn record(
+        &self,
+        artifact: &domain::Artifact,
+        release: &Release,
+        built_at: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.record_released(
+            &release.artifact_tag,
+            ReleasedArtifact {
+                artifact_name: release.artifact_name.clone(),
+                registry_url: release.registry_url.clone(),
+                digest: release.digest.clone().unwrap_or_default(),
+                platforms: release.platforms.clone(),
+                source_repo: artifact.source_info().0.to_string(),
+                // the source may have been removed since the build
+                commit: git::head_commit(artifact.context()).unwrap_or_default(),
+                built_at,
+            },
+        )
+    }
+
+    fn record_released(
+        &self,
+        release_name: &str,
+        released: ReleasedArtifact,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let _guard = self.manifest_lock.lock().unwrap();
+
+        let mut manifest = self
+            .manifest_repository
+            .find(release_name)?
+            .unwrap_or_else(|| ReleaseManifest::new(release_name));
+        manifest.upsert(released);
+
+        self.manifest_repository.save(&manifest)
     }
 
     fn exists(&self, artifact: &domain::Artifact) -> bool {
@@ -28,24 +80,130 @@ impl DockerReleaseRepository {
 
 impl domain::TReleaseRepository for DockerReleaseRepository {
     fn pull(&self, release: &Release) -> Result<(), Box<dyn std::error::Error>> {
-        system::execute_command(Command::new("docker").arg("pull").arg(release.uri()), false)?;
+        system::execute_command(
+            Command::new("docker").arg("pull").arg(release.pinned_uri()),
+            false,
+        )?;
 
         Ok(())
     }
 
+    fn promote(
+        &self,
+        artifact: &domain::Artifact,
+        from: &Release,
+        registry_url: &str,
+        release_name: &str,
+    ) -> Result<Release, Box<dyn std::error::Error>> {
+        let mut release = Release::from_artifact(artifact, release_name, registry_url);
+        release.platforms = from.platforms.clone();
+
+        let digest = match &from.digest {
+            Some(digest) => digest.clone(),
+            None => docker::remote_digest(&from.uri())?,
+        };
+
+        // manifest lists can't be pulled whole, they're copied between registries instead
+        if from.platforms.is_empty() {
+            system::execute_command(
+                Command::new("docker").arg("pull").arg(from.pinned_uri()),
+                false,
+            )?;
+            system::execute_command(
+                Command::new("docker")
+                    .arg("tag")
+                    .arg(from.pinned_uri())
+                    .arg(release.uri()),
+                true,
+            )?;
+            system::execute_command(Command::new("docker").arg("push").arg(release.uri()), false)?;
+        } else {
+            docker::imagetools_copy(&from.pinned_uri(), &release.uri())?;
+        }
+
+        let pushed_digest = docker::remote_digest(&release.uri())?;
+        if pushed_digest != digest {
+            return Err(format!(
+                "Digest mismatch promoting {}: pushed {} but the release is {}",
+                from.uri(),
+                pushed_digest,
+                digest
+            )
+            .into());
+        }
+        release.digest = Some(pushed_digest);
+
+        // the promoted artifact keeps the source and build time of the original
+        let original = {
+            let _guard = self.manifest_lock.lock().unwrap();
+
+            self.manifest_repository
+                .find(&from.artifact_tag)?
+                .and_then(|manifest| {
+                    manifest
+                        .find(&from.artifact_name, &from.registry_url)
+                        .cloned()
+                })
+        };
+        self.record_released(
+            release_name,
+            ReleasedArtifact {
+                artifact_name: release.artifact_name.clone(),
+                registry_url: release.registry_url.clone(),
+                digest: digest.clone(),
+                platforms: release.platforms.clone(),
+                source_repo: original
+                    .as_ref()
+                    .map(|original| original.source_repo.clone())
+                    .unwrap_or_default(),
+                commit: original
+                    .as_ref()
+                    .map(|original| original.commit.clone())
+                    .unwrap_or_default(),
+                built_at: original
+                    .map(|original| original.built_at)
+                    .unwrap_or_default(),
+            },
+        )?;
+
+        Ok(release)
+    }
+
     fn create_for_artifact(
         &self,
         artifact: &domain::Artifact,
         release_name: &str,
         registry_url: &str,
+        platforms: &[String],
     ) -> Result<Release, Box<dyn std::error::Error>> {
+        let mut release = Release::from_artifact(artifact, release_name, registry_url);
+
+        // multi-platform images can't be loaded into docker, buildx rebuilds them from its cache and pushes the manifest list
+        if !platforms.is_empty() {
+            docker::buildx_build(
+                artifact.context(),
+                artifact.dockerfile(),
+                &release.uri(),
+                &[],
+                platforms,
+                true,
+            )?;
+            release.platforms = platforms.to_vec();
+            release.digest = Some(docker::remote_digest(&release.uri())?);
+            self.record(
+                artifact,
+                &release,
+                SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            )?;
+
+            return Ok(release);
+        }
+
         // check image exists locally
         if self.exists(&artifact) == false {
             return Err(format!("Artifact {} not found", artifact.name()).into());
         }
 
-        let release = Release::from_artifact(artifact, release_name, registry_url);
-
         system::execute_command(
             Command::new("docker")
                 .arg("tag")
@@ -55,6 +213,12 @@ impl domain::TReleaseRepository for DockerReleaseRepository {
         )?;
 
         system::execute_command(Command::new("docker").arg("push").arg(release.uri()), false)?;
+        release.digest = Some(docker::remote_digest(&release.uri())?);
+        self.record(
+            artifact,
+            &release,
+            docker::image_created_at(artifact.name())?,
+        )?;
 
         Ok(release)
     }
@@ -0,0 +1,72 @@
+use crate::domain::{ReleaseManifest, TReleaseManifestRepository};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// Manifests are kept as `<name>.json` in the releases folder, meant to be committed with the project
+pub struct InMemoryReleaseManifestRepository {
+    releases_dir: PathBuf,
+}
+
+// implementations ====================================
+
+impl InMemoryReleaseManifestRepository {
+    pub fn new(releases_dir: &Path) -> Self {
+        Self {
+            releases_dir: releases_dir.to_path_buf(),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.releases_dir.join(format!("{}.json", name))
+    }
+}
+
+impl TReleaseManifestRepository for InMemoryReleaseManifestRepository {
+    fn save(&self, manifest: &ReleaseManifest) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&self.releases_dir)?;
+        serde_json::to_writer_pretty(File::create(self.path(&manifest.name))?, manifest)?;
+
+        Ok(())
+    }
+
+    fn find(&self, name: &str) -> Result<Option<ReleaseManifest>, Box<dyn std::error::Error>> {
+        let path = self.path(name);
+        if !std::fs::exists(&path)? {
+            return Ok(None);
+        }
+
+        let manifest: ReleaseManifest = serde_json::from_reader(File::open(&path)?)?;
+
+        Ok(Some(manifest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ReleasedArtifact;
+
+    #[test]
+    fn manifest_round_trip() {
+        let root = tempfile::TempDir::new().unwrap();
+        let repository = InMemoryReleaseManifestRepository::new(&root.path().join("releases"));
+        let mut manifest = ReleaseManifest::new("v0.1.0");
+        manifest.upsert(ReleasedArtifact {
+            artifact_name: "op-node".to_string(),
+            registry_url: "wakeuplabs".to_string(),
+            digest: "sha256:abc".to_string(),
+            platforms: vec![],
+            source_repo: "ethereum-optimism/optimism".to_string(),
+            commit: "def".to_string(),
+            built_at: 1700000000,
+        });
+
+        assert!(repository.find("v0.1.0").unwrap().is_none());
+
+        repository.save(&manifest).unwrap();
+        assert!(root.path().join("releases/v0.1.0.json").exists());
+        assert_eq!(repository.find("v0.1.0").unwrap(), Some(manifest));
+    }
+}
@@ -33,7 +33,7 @@ impl TReleaseRunner for DockerReleaseRunner {
                 .args(env_args.concat())
                 .arg("-v")
                 .arg(format!("{}:{}", volume.display(), "/shared"))
-                .arg(release.uri()),
+                .arg(release.pinned_uri()),
             false,
         )?;
 
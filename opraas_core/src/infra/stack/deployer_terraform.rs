@@ -1,24 +1,40 @@
 use crate::{
-    domain::{Deployment, Stack, TDeploymentRepository, TStackInfraDeployer},
-    infra::deployment::InMemoryDeploymentRepository,
+    domain::{
+        ArtifactKind, Deployment, LogsOptions, Stack, StackComponent, TDeploymentRepository,
+        TReleaseManifestRepository, TReleaseRegistry, TStackInfraDeployer, TStackLogs,
+    },
+    helm,
+    infra::{
+        deployment::InMemoryDeploymentRepository,
+        release::{InMemoryReleaseManifestRepository, OciReleaseRegistry},
+    },
     system, yaml,
 };
+use log::warn;
+use serde_json::Value;
 use std::{
     collections::HashMap,
     fs::{self, File},
+    path::Path,
     process::Command,
 };
 
 pub struct TerraformDeployer {
     deployment_repository: Box<dyn TDeploymentRepository>,
+    release_registry: Box<dyn TReleaseRegistry>,
 }
 
+// as declared in infra/aws/main.tf
+const HELM_RELEASE_NAME: &str = "opraas";
+const HELM_NAMESPACE: &str = "opraas";
+
 // implementations ================================================
 
 impl TerraformDeployer {
     pub fn new(root: &std::path::PathBuf) -> Self {
         Self {
             deployment_repository: Box::new(InMemoryDeploymentRepository::new(root)),
+            release_registry: Box::new(OciReleaseRegistry::new()),
         }
     }
 
@@ -47,30 +63,63 @@ impl TerraformDeployer {
 
         // artifacts images =============================================
 
-        updates.insert("node.image.tag", depl.release_name.clone());
+        updates.insert("node.image.tag", depl.release_for("op-node").to_string());
         updates.insert(
             "node.image.repository",
             format!("{}/{}", depl.registry_url, "op-node"),
         );
 
-        updates.insert("batcher.image.tag", depl.release_name.clone());
+        updates.insert(
+            "batcher.image.tag",
+            depl.release_for("op-batcher").to_string(),
+        );
         updates.insert(
             "batcher.image.repository",
             format!("{}/{}", depl.registry_url, "op-batcher"),
         );
 
-        updates.insert("proposer.image.tag", depl.release_name.clone());
+        updates.insert(
+            "proposer.image.tag",
+            depl.release_for("op-proposer").to_string(),
+        );
         updates.insert(
             "proposer.image.repository",
             format!("{}/{}", depl.registry_url, "op-proposer"),
         );
 
-        updates.insert("geth.image.tag", depl.release_name.clone());
+        updates.insert("geth.image.tag", depl.release_for("op-geth").to_string());
         updates.insert(
             "geth.image.repository",
             format!("{}/{}", depl.registry_url, "op-geth"),
         );
 
+        // pin the images to the digests they were released with, tags can be pushed again
+        let release_manifest_repository = InMemoryReleaseManifestRepository::new(&stack.releases);
+        for (key, artifact_name) in [
+            ("node.image.digest", "op-node"),
+            ("batcher.image.digest", "op-batcher"),
+            ("proposer.image.digest", "op-proposer"),
+            ("geth.image.digest", "op-geth"),
+        ] {
+            let manifest = release_manifest_repository.find(depl.release_for(artifact_name))?;
+            if let Some(digest) = manifest
+                .as_ref()
+                .and_then(|manifest| manifest.digest_for(artifact_name, &depl.registry_url))
+            {
+                updates.insert(key, digest.to_string());
+            }
+        }
+
+        let explorer_manifest = release_manifest_repository.find(depl.release_for("op-explorer"))?;
+        if let Some((repository, tag)) = helm::explorer_image(
+            depl,
+            explorer_manifest.as_ref(),
+            self.release_registry.as_ref(),
+        ) {
+            updates.insert("blockscout-stack.blockscout.image.repository", repository);
+            updates.insert("blockscout-stack.blockscout.image.tag", tag);
+        }
+
         // chain settings ================================================
 
         updates.insert("chain.id", depl.network_config.l2_chain_id.to_string());
@@ -86,6 +135,51 @@ impl TerraformDeployer {
 
         Ok(())
     }
+
+    fn write_kubeconfig(&self, infra_artifacts: &Path, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let outputs: Value = serde_json::from_reader(File::open(infra_artifacts)?)?;
+        let (cluster_name, region) = cluster_of(&outputs)?;
+
+        system::execute_command(
+            Command::new("aws")
+                .args(["eks", "update-kubeconfig", "--name", cluster_name])
+                .args(["--region", region])
+                .arg("--kubeconfig")
+                .arg(path),
+            true,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Cluster name and region out of `terraform output -json`
+fn cluster_of(outputs: &Value) -> Result<(&str, &str), Box<dyn std::error::Error>> {
+    let cluster_name = outputs["cluster_name"]["value"]
+        .as_str()
+        .ok_or("cluster_name not found in infra artifacts")?;
+    let region = outputs["region"]["value"]
+        .as_str()
+        .ok_or("region not found in infra artifacts")?;
+
+    Ok((cluster_name, region))
+}
+
+/// Fails unless the current terraform state is the cluster recorded for the deployment, the state is shared by the
+/// whole project so it may back another deployment
+fn ensure_state_owner(deployment: &str, recorded: &Value, current: &Value) -> Result<(), Box<dyn std::error::Error>> {
+    let (recorded_cluster, recorded_region) = cluster_of(recorded)?;
+    let (current_cluster, current_region) = cluster_of(current)?;
+
+    if (recorded_cluster, recorded_region) != (current_cluster, current_region) {
+        return Err(format!(
+            "Terraform state backs cluster {} in {}, not {} in {} of deployment {}. Refusing to destroy it",
+            current_cluster, current_region, recorded_cluster, recorded_region, deployment
+        )
+        .into());
+    }
+
+    Ok(())
 }
 
 impl TStackInfraDeployer for TerraformDeployer {
@@ -98,21 +192,7 @@ impl TStackInfraDeployer for TerraformDeployer {
         self.create_values_file(stack, values.path().to_str().unwrap())?;
 
         // copy addresses.json and artifacts.zip to helm/config so it can be loaded by it
-        let config_dir = stack.helm.join("config");
-        fs::create_dir_all(&config_dir)?;
-
-        let unzipped_artifacts = tempfile::TempDir::new()?;
-        zip_extract::extract(
-            File::open(contracts_artifacts)?,
-            &unzipped_artifacts.path(),
-            true,
-        )?;
-
-        fs::copy(contracts_artifacts, config_dir.join("artifacts.zip"))?;
-        fs::copy(
-            unzipped_artifacts.path().join("addresses.json"),
-            config_dir.join("addresses.json"),
-        )?;
+        helm::write_chart_config(&stack.helm, contracts_artifacts)?;
 
         // deploy using terraform
 
@@ -159,4 +239,198 @@ impl TStackInfraDeployer for TerraformDeployer {
 
         Ok(deployment)
     }
+
+    fn destroy(&self, stack: &Stack) -> Result<(), Box<dyn std::error::Error>> {
+        let deployment = stack
+            .deployment
+            .as_ref()
+            .ok_or("Stack does not contain deployment")?;
+        let infra_artifacts = match &deployment.infra_artifacts {
+            Some(infra_artifacts) => infra_artifacts,
+            None => {
+                return Err(format!(
+                    "Deployment {} has no infra artifacts, refusing to destroy infra it may not own",
+                    deployment.name
+                )
+                .into())
+            }
+        };
+        let recorded: Value = serde_json::from_reader(File::open(infra_artifacts)?)?;
+
+        // values file is still referenced by the helm release resource
+        let values = tempfile::NamedTempFile::new()?;
+        self.create_values_file(stack, values.path().to_str().unwrap())?;
+        let values_var = format!("-var=values_file_path={}", values.path().to_str().unwrap());
+
+        system::execute_command(
+            Command::new("terraform")
+                .arg("init")
+                .current_dir(stack.aws.to_str().unwrap()),
+            false,
+        )?;
+
+        let current: Value = serde_json::from_str(&system::execute_command(
+            Command::new("terraform")
+                .arg("output")
+                .arg("-json")
+                .current_dir(stack.aws.to_str().unwrap()),
+            true,
+        )?)?;
+        if current
+            .as_object()
+            .is_some_and(|outputs| outputs.is_empty())
+        {
+            warn!(
+                "Terraform state is empty, the infra of {} is already gone",
+                deployment.name
+            );
+            return Ok(());
+        }
+        ensure_state_owner(&deployment.name, &recorded, &current)?;
+
+        // uninstall the chain release first so kubernetes releases its load balancers and volumes
+        system::execute_command(
+            Command::new("terraform")
+                .arg("destroy")
+                .arg("-auto-approve")
+                .arg("-target=helm_release.opraas")
+                .arg(&values_var)
+                .current_dir(stack.aws.to_str().unwrap()),
+            false,
+        )?;
+
+        system::execute_command(
+            Command::new("terraform")
+                .arg("destroy")
+                .arg("-auto-approve")
+                .arg(&values_var)
+                .current_dir(stack.aws.to_str().unwrap()),
+            false,
+        )?;
+
+        Ok(())
+    }
+
+    fn upgrade(&self, stack: &Stack, artifacts: &[ArtifactKind]) -> Result<(), Box<dyn std::error::Error>> {
+        let deployment = stack
+            .deployment
+            .as_ref()
+            .ok_or("Stack does not contain deployment")?;
+        let contracts_artifacts = deployment
+            .contracts_artifacts
+            .as_ref()
+            .ok_or("Deployment has no contracts artifacts, deploy its contracts first")?;
+        let infra_artifacts = match &deployment.infra_artifacts {
+            Some(infra_artifacts) => infra_artifacts,
+            None => return Err("Deployment has no infra to upgrade, deploy it first".into()),
+        };
+
+        // create values file
+        let values = tempfile::NamedTempFile::new()?;
+        self.create_values_file(stack, values.path().to_str().unwrap())?;
+
+        helm::write_chart_config(&stack.helm, contracts_artifacts)?;
+
+        // terraform owns the chain release, upgrading it with helm directly would be reverted on the next apply
+        system::execute_command(
+            Command::new("terraform")
+                .arg("init")
+                .current_dir(stack.aws.to_str().unwrap()),
+            false,
+        )?;
+        system::execute_command(
+            Command::new("terraform")
+                .arg("apply")
+                .arg("-auto-approve")
+                .arg("-target=helm_release.opraas")
+                .arg(format!(
+                    "-var=values_file_path={}",
+                    values.path().to_str().unwrap()
+                ))
+                .current_dir(stack.aws.to_str().unwrap()),
+            false,
+        )?;
+
+        // use a throwaway kubeconfig so we don't touch the user's current context
+        let kubeconfig = tempfile::NamedTempFile::new()?;
+        self.write_kubeconfig(infra_artifacts, kubeconfig.path())?;
+
+        for artifact in artifacts {
+            for deployment in helm::chart_deployments(artifact, HELM_RELEASE_NAME) {
+                helm::wait_for_rollout(HELM_NAMESPACE, &deployment, Some(kubeconfig.path()))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TStackLogs for TerraformDeployer {
+    fn logs(
+        &self,
+        stack: &Stack,
+        components: &[StackComponent],
+        options: &LogsOptions,
+        on_line: &(dyn Fn(&str, &str) + Sync),
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let deployment = stack.deployment.as_ref().ok_or("Deployment not found")?;
+        let infra_artifacts = match &deployment.infra_artifacts {
+            Some(infra_artifacts) => infra_artifacts,
+            None => return Err("Deployment has no infra, deploy it first".into()),
+        };
+
+        let kubeconfig = tempfile::NamedTempFile::new()?;
+        self.write_kubeconfig(infra_artifacts, kubeconfig.path())?;
+
+        let selectors: Vec<String> = components
+            .iter()
+            .flat_map(|component| helm::chart_pod_selectors(component, HELM_RELEASE_NAME))
+            .collect();
+
+        helm::stream_logs(
+            HELM_NAMESPACE,
+            &selectors,
+            options,
+            Some(kubeconfig.path()),
+            on_line,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn outputs(cluster_name: &str, region: &str) -> Value {
+        json!({
+            "cluster_name": { "value": cluster_name },
+            "region": { "value": region },
+        })
+    }
+
+    #[test]
+    fn should_only_destroy_the_deployment_cluster() {
+        let recorded = outputs("opraas-staging", "us-east-1");
+
+        assert!(ensure_state_owner(
+            "staging",
+            &recorded,
+            &outputs("opraas-staging", "us-east-1")
+        )
+        .is_ok());
+        assert_eq!(
+            ensure_state_owner("staging", &recorded, &outputs("opraas-prod", "us-east-1"))
+                .unwrap_err()
+                .to_string(),
+            "Terraform state backs cluster opraas-prod in us-east-1, not opraas-staging in us-east-1 of deployment \
+             staging. Refusing to destroy it"
+        );
+        assert!(ensure_state_owner(
+            "staging",
+            &recorded,
+            &outputs("opraas-staging", "eu-west-1")
+        )
+        .is_err());
+    }
 }
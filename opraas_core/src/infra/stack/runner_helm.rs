@@ -1,18 +1,20 @@
 use log::info;
 
 use crate::{
-    domain::{Stack, TStackRunner},
+    domain::{
+        ArtifactKind, Deployment, LogsOptions, Stack, StackComponent, TReleaseManifestRepository, TReleaseRegistry,
+        TStackInfraDeployer, TStackLogs, TStackRunner,
+    },
+    helm,
+    infra::release::{InMemoryReleaseManifestRepository, OciReleaseRegistry},
     system, yaml,
 };
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-    process::Command,
-};
+use std::{collections::HashMap, process::Command};
 
 pub struct HelmStackRunner {
     release_name: String,
     namespace: String,
+    release_registry: Box<dyn TReleaseRegistry>,
 }
 
 // implementations ============================================================
@@ -22,9 +24,14 @@ impl HelmStackRunner {
         Self {
             release_name: release_name.to_string(),
             namespace: namespace.to_string(),
+            release_registry: Box::new(OciReleaseRegistry::new()),
         }
     }
 
+    pub fn helm_release_name(&self) -> String {
+        format!("op-ruaas-runner-{}", &self.release_name)
+    }
+
     fn build_dependencies(&self, stack: &Stack) -> Result<(), Box<dyn std::error::Error>> {
         let repo_dependencies = [
             (
@@ -124,30 +131,63 @@ impl HelmStackRunner {
 
         // artifacts images =============================================
 
-        updates.insert("node.image.tag", depl.release_name.clone());
+        updates.insert("node.image.tag", depl.release_for("op-node").to_string());
         updates.insert(
             "node.image.repository",
             format!("{}/{}", depl.registry_url, "op-node"),
         );
 
-        updates.insert("batcher.image.tag", depl.release_name.clone());
+        updates.insert(
+            "batcher.image.tag",
+            depl.release_for("op-batcher").to_string(),
+        );
         updates.insert(
             "batcher.image.repository",
             format!("{}/{}", depl.registry_url, "op-batcher"),
         );
 
-        updates.insert("proposer.image.tag", depl.release_name.clone());
+        updates.insert(
+            "proposer.image.tag",
+            depl.release_for("op-proposer").to_string(),
+        );
         updates.insert(
             "proposer.image.repository",
             format!("{}/{}", depl.registry_url, "op-proposer"),
         );
 
-        updates.insert("geth.image.tag", depl.release_name.clone());
+        updates.insert("geth.image.tag", depl.release_for("op-geth").to_string());
         updates.insert(
             "geth.image.repository",
             format!("{}/{}", depl.registry_url, "op-geth"),
         );
 
+        // pin the images to the digests they were released with, tags can be pushed again
+        let release_manifest_repository = InMemoryReleaseManifestRepository::new(&stack.releases);
+        for (key, artifact_name) in [
+            ("node.image.digest", "op-node"),
+            ("batcher.image.digest", "op-batcher"),
+            ("proposer.image.digest", "op-proposer"),
+            ("geth.image.digest", "op-geth"),
+        ] {
+            let manifest = release_manifest_repository.find(depl.release_for(artifact_name))?;
+            if let Some(digest) = manifest
+                .as_ref()
+                .and_then(|manifest| manifest.digest_for(artifact_name, &depl.registry_url))
+            {
+                updates.insert(key, digest.to_string());
+            }
+        }
+
+        let explorer_manifest = release_manifest_repository.find(depl.release_for("op-explorer"))?;
+        if let Some((repository, tag)) = helm::explorer_image(
+            depl,
+            explorer_manifest.as_ref(),
+            self.release_registry.as_ref(),
+        ) {
+            updates.insert("blockscout-stack.blockscout.image.repository", repository);
+            updates.insert("blockscout-stack.blockscout.image.tag", tag);
+        }
+
         // chain settings ================================================
 
         updates.insert("chain.id", depl.network_config.l2_chain_id.to_string());
@@ -191,8 +231,14 @@ impl HelmStackRunner {
 
 impl TStackRunner for HelmStackRunner {
     fn run(&self, stack: &Stack) -> Result<(), Box<dyn std::error::Error>> {
-        let deployment = stack.deployment.as_ref().unwrap();
-        let contracts_artifacts = deployment.contracts_artifacts.as_ref().unwrap();
+        let deployment = stack
+            .deployment
+            .as_ref()
+            .ok_or("Stack does not contain deployment")?;
+        let contracts_artifacts = deployment
+            .contracts_artifacts
+            .as_ref()
+            .ok_or("Deployment has no contracts artifacts, deploy its contracts first")?;
 
         // add repos, install pre-requisites and build dependencies
         self.build_dependencies(stack)?;
@@ -202,28 +248,14 @@ impl TStackRunner for HelmStackRunner {
         self.create_values_file(stack, values.path().to_str().unwrap())?;
 
         // copy addresses.json and artifacts.zip to helm/config so it can be loaded by it
-        let config_dir = stack.helm.join("config");
-        fs::create_dir_all(&config_dir)?;
-
-        let unzipped_artifacts = tempfile::TempDir::new()?;
-        zip_extract::extract(
-            File::open(contracts_artifacts)?,
-            &unzipped_artifacts.path(),
-            true,
-        )?;
-
-        fs::copy(contracts_artifacts, config_dir.join("artifacts.zip"))?;
-        fs::copy(
-            unzipped_artifacts.path().join("addresses.json"),
-            config_dir.join("addresses.json"),
-        )?;
+        helm::write_chart_config(&stack.helm, contracts_artifacts)?;
 
         // install core infrastructure
 
         system::execute_command(
             Command::new("helm")
                 .arg("install")
-                .arg(format!("op-ruaas-runner-{}", &self.release_name))
+                .arg(self.helm_release_name())
                 .arg("-f")
                 .arg(values.path().to_str().unwrap())
                 .arg("--namespace")
@@ -247,14 +279,14 @@ impl TStackRunner for HelmStackRunner {
                 .arg(&self.namespace),
             true,
         )?;
-        if running_releases.contains(&format!("op-ruaas-runner-{}", &self.release_name)) == false {
+        if !running_releases.contains(&self.helm_release_name()) {
             return Ok(());
         }
 
         system::execute_command(
             Command::new("helm")
                 .arg("uninstall")
-                .arg(format!("op-ruaas-runner-{}", &self.release_name))
+                .arg(self.helm_release_name())
                 .arg("--namespace")
                 .arg(&self.namespace),
             false,
@@ -263,3 +295,72 @@ impl TStackRunner for HelmStackRunner {
         Ok(())
     }
 }
+
+impl TStackInfraDeployer for HelmStackRunner {
+    fn deploy(&self, stack: &Stack) -> Result<Deployment, Box<dyn std::error::Error>> {
+        self.run(stack)?;
+
+        Ok(stack
+            .deployment
+            .as_ref()
+            .ok_or("Stack does not contain deployment")?
+            .clone())
+    }
+
+    fn destroy(&self, _stack: &Stack) -> Result<(), Box<dyn std::error::Error>> {
+        self.stop()
+    }
+
+    fn upgrade(&self, stack: &Stack, artifacts: &[ArtifactKind]) -> Result<(), Box<dyn std::error::Error>> {
+        let deployment = stack
+            .deployment
+            .as_ref()
+            .ok_or("Stack does not contain deployment")?;
+        let contracts_artifacts = deployment
+            .contracts_artifacts
+            .as_ref()
+            .ok_or("Deployment has no contracts artifacts, deploy its contracts first")?;
+
+        let values = tempfile::NamedTempFile::new()?;
+        self.create_values_file(stack, values.path().to_str().unwrap())?;
+
+        helm::write_chart_config(&stack.helm, contracts_artifacts)?;
+
+        system::execute_command(
+            Command::new("helm")
+                .arg("upgrade")
+                .arg(self.helm_release_name())
+                .arg("-f")
+                .arg(values.path().to_str().unwrap())
+                .arg("--namespace")
+                .arg(&self.namespace)
+                .arg(stack.helm.to_str().unwrap()),
+            false,
+        )?;
+
+        for artifact in artifacts {
+            for deployment in helm::chart_deployments(artifact, &self.helm_release_name()) {
+                helm::wait_for_rollout(&self.namespace, &deployment, None)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TStackLogs for HelmStackRunner {
+    fn logs(
+        &self,
+        _stack: &Stack,
+        components: &[StackComponent],
+        options: &LogsOptions,
+        on_line: &(dyn Fn(&str, &str) + Sync),
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let selectors: Vec<String> = components
+            .iter()
+            .flat_map(|component| helm::chart_pod_selectors(component, &self.helm_release_name()))
+            .collect();
+
+        helm::stream_logs(&self.namespace, &selectors, options, None, on_line)
+    }
+}
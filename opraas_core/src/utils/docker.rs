@@ -0,0 +1,252 @@
+use crate::system::execute_command;
+use std::{path::Path, process::Command};
+
+// the default docker driver can't build for several platforms at once, this builder uses the container driver
+pub const BUILDX_BUILDER: &str = "opruaas-builder";
+
+/// Builds `tag` for every platform with buildx, `labels` are `key=value` pairs. Pushing uploads a manifest
+/// list, otherwise a single platform image is loaded into docker and multi-platform ones stay in the buildx cache.
+pub fn buildx_build(
+    context: &Path,
+    dockerfile: &Path,
+    tag: &str,
+    labels: &[String],
+    platforms: &[String],
+    push: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    ensure_buildx_builder()?;
+
+    execute_command(
+        Command::new("docker")
+            .args(buildx_args(dockerfile, tag, labels, platforms, push))
+            .current_dir(context),
+        false,
+    )?;
+
+    Ok(())
+}
+
+/// Digest of the image or manifest list `uri` points to, as the registry serves it
+pub fn remote_digest(uri: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let manifest = execute_command(
+        Command::new("docker")
+            .args([
+                "buildx",
+                "imagetools",
+                "inspect",
+                "--format",
+                "{{json .Manifest}}",
+            ])
+            .arg(uri),
+        true,
+    )?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest)?;
+
+    match manifest["digest"].as_str() {
+        Some(digest) => Ok(digest.to_string()),
+        None => Err(format!("No digest found for {}", uri).into()),
+    }
+}
+
+/// Copies `from` to `to` between registries, manifest lists included, without going through the local docker
+pub fn imagetools_copy(from: &str, to: &str) -> Result<(), Box<dyn std::error::Error>> {
+    execute_command(
+        Command::new("docker")
+            .args(["buildx", "imagetools", "create", "--tag", to])
+            .arg(from),
+        false,
+    )?;
+
+    Ok(())
+}
+
+/// When the local `image` was built, as a unix timestamp
+pub fn image_created_at(image: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    let created = execute_command(
+        Command::new("docker")
+            .args(["image", "inspect", "--format", "{{.Created}}"])
+            .arg(image),
+        true,
+    )?;
+
+    unix_timestamp(&created).ok_or(format!("Unexpected creation time {} for {}", created.trim(), image).into())
+}
+
+fn ensure_buildx_builder() -> Result<(), Box<dyn std::error::Error>> {
+    if execute_command(
+        Command::new("docker")
+            .args(["buildx", "inspect"])
+            .arg(BUILDX_BUILDER),
+        true,
+    )
+    .is_ok()
+    {
+        return Ok(());
+    }
+
+    execute_command(
+        Command::new("docker").args([
+            "buildx",
+            "create",
+            "--name",
+            BUILDX_BUILDER,
+            "--driver",
+            "docker-container",
+        ]),
+        true,
+    )?;
+
+    Ok(())
+}
+
+fn buildx_args(dockerfile: &Path, tag: &str, labels: &[String], platforms: &[String], push: bool) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "buildx".to_string(),
+        "build".to_string(),
+        "--builder".to_string(),
+        BUILDX_BUILDER.to_string(),
+        "--platform".to_string(),
+        platforms.join(","),
+        "-t".to_string(),
+        tag.to_string(),
+        "-f".to_string(),
+        dockerfile.display().to_string(),
+    ];
+    for label in labels {
+        args.push("--label".to_string());
+        args.push(label.to_string());
+    }
+
+    match (push, platforms.len()) {
+        (true, _) => args.push("--push".to_string()),
+        (false, 1) => args.push("--load".to_string()),
+        (false, _) => {}
+    }
+    args.push(".".to_string());
+
+    args
+}
+
+// docker reports times in utc like 2024-05-01T12:34:56.123456789Z
+fn unix_timestamp(rfc3339: &str) -> Option<u64> {
+    let (date, time) = rfc3339.trim().trim_end_matches('Z').split_once('T')?;
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+    let time: Vec<i64> = time
+        .split(':')
+        .map(|part| part.split('.').next()?.parse().ok())
+        .collect::<Option<_>>()?;
+    let ([year, month, day], [hours, minutes, seconds]) = (date.as_slice(), time.as_slice()) else {
+        return None;
+    };
+
+    // days since the epoch, see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if *month <= 2 { year - 1 } else { *year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+
+    u64::try_from(days * 86400 + hours * 3600 + minutes * 60 + seconds).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_load_single_platform_builds() {
+        let platforms = vec!["linux/amd64".to_string(), "linux/arm64".to_string()];
+        let labels = vec!["opruaas.fingerprint=abc".to_string()];
+        let dockerfile = Path::new("Dockerfile");
+
+        assert_eq!(
+            buildx_args(dockerfile, "op-node", &[], &platforms, false).join(" "),
+            "buildx build --builder opruaas-builder --platform linux/amd64,linux/arm64 -t op-node -f Dockerfile ."
+        );
+        assert_eq!(
+            buildx_args(dockerfile, "op-node", &labels, &platforms[..1], false).join(" "),
+            "buildx build --builder opruaas-builder --platform linux/amd64 -t op-node -f Dockerfile --label opruaas.fingerprint=abc --load ."
+        );
+        assert_eq!(
+            buildx_args(dockerfile, "wakeuplabs/op-node:v1", &[], &platforms, true).join(" "),
+            "buildx build --builder opruaas-builder --platform linux/amd64,linux/arm64 -t wakeuplabs/op-node:v1 -f Dockerfile --push ."
+        );
+    }
+
+    #[test]
+    fn should_parse_docker_creation_times() {
+        assert_eq!(unix_timestamp("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            unix_timestamp("2024-02-29T12:34:56.123456789Z\n"),
+            Some(1709210096)
+        );
+        assert_eq!(unix_timestamp("yesterday"), None);
+    }
+
+    // needs docker with buildx, run with `cargo test -- --ignored`
+    #[test]
+    #[ignore]
+    fn should_cross_compile_go_artifacts() {
+        let (platform, elf_machine) = match std::env::consts::ARCH {
+            "aarch64" => ("linux/amd64", 0x3e),
+            _ => ("linux/arm64", 0xb7),
+        };
+
+        // stands in for the monorepo, its makefiles build for TARGETOS/TARGETARCH
+        let context = tempfile::TempDir::new().unwrap();
+        let op_node = context.path().join("op-node");
+        std::fs::create_dir_all(&op_node).unwrap();
+        std::fs::write(
+            context.path().join("go.mod"),
+            "module example.com/op\n\ngo 1.22\n",
+        )
+        .unwrap();
+        std::fs::write(context.path().join("go.sum"), "").unwrap();
+        std::fs::write(op_node.join("main.go"), "package main\n\nfunc main() {}\n").unwrap();
+        std::fs::write(
+            op_node.join("Makefile"),
+            "op-node:\n\tenv GOOS=$(TARGETOS) GOARCH=$(TARGETARCH) CGO_ENABLED=0 go build -o ./bin/op-node .\n",
+        )
+        .unwrap();
+
+        let dockerfile = Path::new(env!("CARGO_MANIFEST_DIR")).join("docker/node.dockerfile");
+        buildx_build(
+            context.path(),
+            &dockerfile,
+            "opruaas-cross-compile-test",
+            &[],
+            &[platform.to_string()],
+            false,
+        )
+        .unwrap();
+
+        let container = execute_command(
+            Command::new("docker").args([
+                "create",
+                "--platform",
+                platform,
+                "opruaas-cross-compile-test",
+            ]),
+            true,
+        )
+        .unwrap();
+        let binary = context.path().join("op-node.bin");
+        execute_command(
+            Command::new("docker")
+                .arg("cp")
+                .arg(format!("{}:/usr/local/bin/op-node", container.trim()))
+                .arg(&binary),
+            true,
+        )
+        .unwrap();
+        execute_command(Command::new("docker").args(["rm", container.trim()]), true).unwrap();
+
+        // e_machine of the elf header
+        let binary = std::fs::read(binary).unwrap();
+        assert_eq!(u16::from_le_bytes([binary[18], binary[19]]), elf_machine);
+    }
+}
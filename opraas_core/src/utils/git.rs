@@ -1,28 +1,340 @@
+use std::collections::HashMap;
 use std::fs;
-use std::io::Cursor;
-use std::path::Path;
-
-use git2::{ObjectType, Repository};
-
-pub fn clone_tag(source_repo: &str, source_tag: &str, dst_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let repo = Repository::clone(
-        &format!("https://github.com/{}", source_repo), dst_path)?;
-
-    // Lookup the tag reference
-    let tag_ref = format!("refs/tags/{}", source_tag);
-    let reference = repo.find_reference(&tag_ref)?;
-    
-    // Resolve the reference to the tag object
-    let tag_oid = reference.target().ok_or_else(|| git2::Error::from_str("Invalid tag reference"))?;
-    let tag_object = repo.find_object(tag_oid, Some(ObjectType::Any))?;
-    
-    // Checkout the tag
-    repo.checkout_tree(&tag_object, None)?;
-    repo.set_head(&tag_ref)?;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use git2::{
+    AutotagOption, Commit, Config, Cred, CredentialType, Direction, FetchOptions, ObjectType, Oid, Progress,
+    RemoteCallbacks, Repository, StatusOptions,
+};
+
+/// Checks out `source_ref`, a branch, tag or commit sha, at `dst_path`. Objects are fetched shallowly into a bare
+/// repository under `cache_dir` shared by every checkout of the same repository, which copies only the objects of
+/// its commit. Returns the resolved commit.
+pub fn clone_ref(
+    source_repo: &str,
+    source_ref: &str,
+    dst_path: &str,
+    cache_dir: &Path,
+    on_progress: &dyn Fn(Progress),
+) -> Result<String, Box<dyn std::error::Error>> {
+    let url = repo_url(source_repo);
+    let cache_lock = cache_lock(&cache_path(cache_dir, &url));
+    let _guard = cache_lock.lock().unwrap();
+
+    let (cache, commit) = fetch_into_cache(source_repo, source_ref, cache_dir, on_progress)?;
+
+    let repo = Repository::init(dst_path)?;
+    repo.remote("origin", &url)?;
+    copy_commit(&repo, &cache, commit)?;
+
+    repo.set_head_detached(commit)?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+    Ok(commit.to_string())
+}
+
+/// Moves the checkout at `checkout_path` to `source_ref` in place, fetching it into the `cache_dir` cache like
+/// [`clone_ref`]. Uncommitted changes to tracked files are refused unless `force`, which discards them. Returns the
+/// resolved commit.
+pub fn update_ref(
+    source_repo: &str,
+    source_ref: &str,
+    checkout_path: &Path,
+    cache_dir: &Path,
+    force: bool,
+    on_progress: &dyn Fn(Progress),
+) -> Result<String, Box<dyn std::error::Error>> {
+    let repo = Repository::open(checkout_path)?;
+    if !force && is_dirty(&repo)? {
+        return Err(format!(
+            "{} has uncommitted changes, commit or stash them or use --force to discard them",
+            checkout_path.display()
+        )
+        .into());
+    }
+
+    let url = repo_url(source_repo);
+    let cache_lock = cache_lock(&cache_path(cache_dir, &url));
+    let _guard = cache_lock.lock().unwrap();
+
+    let (cache, commit) = fetch_into_cache(source_repo, source_ref, cache_dir, on_progress)?;
+
+    copy_commit(&repo, &cache, commit)?;
+
+    repo.set_head_detached(commit)?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+
+    // checkouts used to borrow the cache objects, they're self-contained once they moved
+    let alternates_path = repo.path().join("objects/info/alternates");
+    if alternates_path.exists() {
+        fs::remove_file(alternates_path)?;
+    }
+
+    Ok(commit.to_string())
+}
+
+/// Links a local checkout at `dst_path`, copying it where symlinks aren't available. Returns its head commit.
+pub fn link_checkout(src_path: &Path, dst_path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let commit = head_commit(src_path).map_err(|_| format!("{} is not a git checkout", src_path.display()))?;
+
+    if let Some(parent) = dst_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(fs::canonicalize(src_path)?, dst_path)?;
+    #[cfg(not(unix))]
+    copy_dir(src_path, dst_path)?;
+
+    Ok(commit)
+}
+
+pub fn head_commit(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let repo = Repository::open(path)?;
+    let commit = repo.head()?.peel_to_commit()?;
+
+    Ok(commit.id().to_string())
+}
+
+fn cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir.join(format!("{}.git", slug(url)))
+}
+
+// callers hold the cache lock, fetches into the same cache can't run concurrently
+fn fetch_into_cache(
+    source_repo: &str,
+    source_ref: &str,
+    cache_dir: &Path,
+    on_progress: &dyn Fn(Progress),
+) -> Result<(Repository, Oid), Box<dyn std::error::Error>> {
+    let url = repo_url(source_repo);
+    let cache_path = cache_path(cache_dir, &url);
+
+    let cache = match Repository::open_bare(&cache_path) {
+        Ok(cache) => cache,
+        Err(_) => {
+            let cache = Repository::init_bare(&cache_path)?;
+            cache.remote("origin", &url)?;
+            cache
+        }
+    };
+    fetch_ref(&cache, source_ref, is_local(&url), on_progress)
+        .map_err(|e| format!("Failed to fetch {} from {}: {}", source_ref, source_repo, e))?;
+    let commit = resolve_ref(&cache, source_ref)
+        .map_err(|_| format!("Ref {} not found in {}", source_ref, source_repo))?
+        .id();
+
+    Ok((cache, commit))
+}
+
+// packs the commit and its tree from the cache into `repo`, marking it shallow. The checkout doesn't depend on the
+// cache afterwards, so it keeps working when copied somewhere else like a docker build context.
+fn copy_commit(repo: &Repository, cache: &Repository, commit: Oid) -> Result<(), Box<dyn std::error::Error>> {
+    let mut pack = git2::Buf::new();
+    let mut pack_builder = cache.packbuilder()?;
+    pack_builder.insert_commit(commit)?;
+    pack_builder.write_buf(&mut pack)?;
+
+    let odb = repo.odb()?;
+    let mut pack_writer = odb.packwriter()?;
+    pack_writer.write_all(&pack)?;
+    pack_writer.commit()?;
+
+    fs::write(repo.path().join("shallow"), format!("{}\n", commit))?;
+
+    Ok(())
+}
+
+// uncommitted changes to tracked files, untracked ones survive a checkout
+fn is_dirty(repo: &Repository) -> Result<bool, git2::Error> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+
+    Ok(!repo.statuses(Some(&mut options))?.is_empty())
+}
+
+// fetches only what's needed for `source_ref`, a single commit unless the remote is local
+fn fetch_ref(
+    cache: &Repository,
+    source_ref: &str,
+    local: bool,
+    on_progress: &dyn Fn(Progress),
+) -> Result<(), git2::Error> {
+    let mut remote = cache.find_remote("origin")?;
+
+    // find out if the ref is a tag or a branch, anything else has to be a commit sha
+    let connection = remote.connect_auth(Direction::Fetch, Some(credentials_callbacks()), None)?;
+    let remote_refs: Vec<String> = connection
+        .list()?
+        .iter()
+        .map(|head| head.name().to_string())
+        .collect();
+    drop(connection);
+
+    let refspec = if remote_refs.contains(&format!("refs/tags/{}", source_ref)) {
+        format!("+refs/tags/{0}:refs/tags/{0}", source_ref)
+    } else if remote_refs.contains(&format!("refs/heads/{}", source_ref)) {
+        format!("+refs/heads/{0}:refs/remotes/origin/{0}", source_ref)
+    } else {
+        match Oid::from_str(source_ref) {
+            // already fetched
+            Ok(oid) if cache.find_commit(oid).is_ok() => return Ok(()),
+            Ok(_) if source_ref.len() == 40 => source_ref.to_string(),
+            _ => {
+                return Err(git2::Error::from_str(
+                    "no such tag, branch or full commit sha",
+                ))
+            }
+        }
+    };
+
+    let mut callbacks = credentials_callbacks();
+    callbacks.transfer_progress(|progress| {
+        on_progress(progress);
+        true
+    });
+
+    let mut options = FetchOptions::new();
+    options
+        .remote_callbacks(callbacks)
+        .download_tags(AutotagOption::None);
+    // libgit2 can't fetch shallowly from local repositories
+    if !local {
+        options.depth(1);
+    }
+
+    remote.fetch(&[refspec], Some(&mut options), None)
+}
+
+// private repositories authenticate like git does, ssh urls through the ssh agent and https ones through the
+// configured credential helpers
+fn credentials_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+
+    // libgit2 asks again for as long as the credentials are refused
+    let mut attempts = 0;
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str(&format!(
+                "authentication to {} failed, check your ssh agent or git credential helper",
+                url
+            )));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            Cred::ssh_key_from_agent(username.unwrap_or("git"))
+        } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            Cred::credential_helper(&Config::open_default()?, url, username)
+        } else {
+            Cred::default()
+        }
+    });
+
+    callbacks
+}
+
+fn cache_lock(cache_path: &Path) -> Arc<Mutex<()>> {
+    static CACHE_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+
+    CACHE_LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(cache_path.to_path_buf())
+        .or_default()
+        .clone()
+}
+
+fn is_local(url: &str) -> bool {
+    url.starts_with("file://") || Path::new(url).exists()
+}
+
+// cache folder name for a repository url
+fn slug(url: &str) -> String {
+    url.trim_end_matches(".git")
+        .split("://")
+        .last()
+        .unwrap()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+// tags first like before refs were supported, then branches, then anything git can parse like a sha
+fn resolve_ref<'a>(repo: &'a Repository, source_ref: &str) -> Result<Commit<'a>, git2::Error> {
+    let candidates = [
+        format!("refs/tags/{}", source_ref),
+        format!("refs/remotes/origin/{}", source_ref),
+        source_ref.to_string(),
+    ];
+
+    let mut last_error = None;
+    for candidate in candidates {
+        match repo.revparse_single(&candidate) {
+            Ok(object) => return object.peel_to_commit(),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.unwrap())
+}
+
+// owner/repo is a github repository, urls and local repositories are cloned as they are
+fn repo_url(source_repo: &str) -> String {
+    if source_repo.contains("://") || source_repo.starts_with("git@") || Path::new(source_repo).exists() {
+        return source_repo.to_string();
+    }
+
+    format!("https://github.com/{}", source_repo)
+}
+
+#[cfg(not(unix))]
+fn copy_dir(src: &Path, dst: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        match entry.file_type()?.is_dir() {
+            true => copy_dir(&entry.path(), &dst.join(entry.file_name()))?,
+            false => {
+                fs::copy(entry.path(), dst.join(entry.file_name()))?;
+            }
+        }
+    }
 
     Ok(())
 }
 
+/// Head commit of the repository at `path` followed by the blob hash of every uncommitted file, one per line
+pub fn tree_state(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let repo = Repository::open(path)?;
+    let mut state = vec![repo.head()?.peel_to_commit()?.id().to_string()];
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+
+    let mut dirty_files: Vec<String> = vec![];
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let file = match entry.path() {
+            Some(file) => file.to_string(),
+            None => continue,
+        };
+
+        let file_path = Path::new(path).join(&file);
+        let hash = match file_path.is_file() {
+            true => Oid::hash_file(ObjectType::Blob, &file_path)?.to_string(),
+            false => "deleted".to_string(),
+        };
+        dirty_files.push(format!("{} {}", file, hash));
+    }
+    dirty_files.sort();
+    state.extend(dirty_files);
+
+    Ok(state.join("\n"))
+}
+
 pub fn download_release_asset(
     release_repo: &str,
     release_tag: &str,
@@ -32,7 +344,14 @@ pub fn download_release_asset(
     let response = reqwest::blocking::get(&format!(
         "https://raw.githubusercontent.com/{}/refs/tags/{}/{}",
         release_repo, release_tag, asset_path
-    ))?;
+    ))?
+    .error_for_status()
+    .map_err(|e| {
+        format!(
+            "Failed to download {} from {} {}: {}",
+            asset_path, release_repo, release_tag, e
+        )
+    })?;
     let bytes = response.bytes()?;
 
     let dst_dir = Path::new(dst_path).parent().unwrap();
@@ -53,7 +372,14 @@ pub fn download_zipped_asset(
     let response = reqwest::blocking::get(&format!(
         "https://github.com/{}/releases/download/{}/{}.zip",
         release_repo, release_tag, asset
-    ))?;
+    ))?
+    .error_for_status()
+    .map_err(|e| {
+        format!(
+            "Failed to download {} from {} {}: {}",
+            asset, release_repo, release_tag, e
+        )
+    })?;
     let bytes = response.bytes()?;
 
     let target = Path::new(dst_path);
@@ -65,3 +391,143 @@ pub fn download_zipped_asset(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit_file(repo: &Repository, content: &str) -> Oid {
+        fs::write(repo.workdir().unwrap().join("file"), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("file")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parents = match repo.head() {
+            Ok(head) => vec![head.peel_to_commit().unwrap()],
+            Err(_) => vec![],
+        };
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            content,
+            &tree,
+            &parents.iter().collect::<Vec<_>>(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn should_clone_tags_branches_and_commits() {
+        let origin_dir = tempfile::TempDir::new().unwrap();
+        let origin = Repository::init(origin_dir.path()).unwrap();
+        let first = commit_file(&origin, "first");
+        origin
+            .tag_lightweight("v1", &origin.find_object(first, None).unwrap(), false)
+            .unwrap();
+        let second = commit_file(&origin, "second");
+        origin
+            .branch("feature", &origin.find_commit(second).unwrap(), false)
+            .unwrap();
+        let origin_path = origin_dir.path().to_str().unwrap();
+        let src = tempfile::TempDir::new().unwrap();
+        let cache_dir = src.path().join(".cache");
+
+        for (name, source_ref, expected) in [
+            ("tag", "v1", first),
+            ("branch", "feature", second),
+            ("commit", &first.to_string() as &str, first),
+        ] {
+            let dst_path = src.path().join(name);
+
+            let commit = clone_ref(
+                origin_path,
+                source_ref,
+                dst_path.to_str().unwrap(),
+                &cache_dir,
+                &|_| {},
+            )
+            .unwrap();
+
+            assert_eq!(commit, expected.to_string());
+            assert_eq!(head_commit(&dst_path).unwrap(), expected.to_string());
+            assert_eq!(
+                fs::read_to_string(dst_path.join("file")).unwrap(),
+                origin.find_commit(expected).unwrap().message().unwrap()
+            );
+        }
+
+        let missing = clone_ref(
+            origin_path,
+            "missing",
+            src.path().join("missing").to_str().unwrap(),
+            &cache_dir,
+            &|_| {},
+        );
+        assert!(missing.is_err());
+
+        // every checkout is fetched through a single cache but doesn't need it afterwards
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 1);
+        fs::remove_dir_all(&cache_dir).unwrap();
+        for name in ["tag", "branch", "commit"] {
+            let repo = Repository::open(src.path().join(name)).unwrap();
+            assert!(repo.head().unwrap().peel_to_tree().is_ok());
+        }
+    }
+
+    #[test]
+    fn should_update_clean_checkouts_in_place() {
+        let origin_dir = tempfile::TempDir::new().unwrap();
+        let origin = Repository::init(origin_dir.path()).unwrap();
+        let first = commit_file(&origin, "first");
+        origin
+            .tag_lightweight("v1", &origin.find_object(first, None).unwrap(), false)
+            .unwrap();
+        let second = commit_file(&origin, "second");
+        origin
+            .tag_lightweight("v2", &origin.find_object(second, None).unwrap(), false)
+            .unwrap();
+        let origin_path = origin_dir.path().to_str().unwrap();
+        let src = tempfile::TempDir::new().unwrap();
+        let cache_dir = src.path().join(".cache");
+        let dst_path = src.path().join("checkout");
+
+        clone_ref(
+            origin_path,
+            "v1",
+            dst_path.to_str().unwrap(),
+            &cache_dir,
+            &|_| {},
+        )
+        .unwrap();
+
+        // local edits are kept unless forced
+        fs::write(dst_path.join("file"), "edited").unwrap();
+        assert!(update_ref(origin_path, "v2", &dst_path, &cache_dir, false, &|_| {}).is_err());
+        assert_eq!(head_commit(&dst_path).unwrap(), first.to_string());
+        assert_eq!(fs::read_to_string(dst_path.join("file")).unwrap(), "edited");
+
+        let commit = update_ref(origin_path, "v2", &dst_path, &cache_dir, true, &|_| {}).unwrap();
+
+        assert_eq!(commit, second.to_string());
+        assert_eq!(head_commit(&dst_path).unwrap(), second.to_string());
+        assert_eq!(fs::read_to_string(dst_path.join("file")).unwrap(), "second");
+    }
+
+    #[test]
+    fn should_only_prefix_github_to_repository_names() {
+        assert_eq!(
+            repo_url("ethereum-optimism/optimism"),
+            "https://github.com/ethereum-optimism/optimism"
+        );
+        assert_eq!(
+            repo_url("git@github.com:acme/optimism.git"),
+            "git@github.com:acme/optimism.git"
+        );
+        assert_eq!(
+            repo_url("https://gitlab.com/acme/optimism.git"),
+            "https://gitlab.com/acme/optimism.git"
+        );
+    }
+}
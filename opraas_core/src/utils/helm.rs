@@ -0,0 +1,421 @@
+use crate::{
+    domain::{ArtifactKind, Deployment, LogsOptions, Release, ReleaseManifest, StackComponent, TReleaseRegistry},
+    system,
+};
+use log::warn;
+use serde_json::Value;
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Command, Stdio},
+};
+
+const ROLLOUT_TIMEOUT: &str = "10m";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PodStatus {
+    pub name: String,
+    /// pod phase, or the reason its containers are waiting (e.g. CrashLoopBackOff)
+    pub status: String,
+    pub ready: bool,
+    pub restarts: u64,
+}
+
+/// copies addresses.json and artifacts.zip to helm/config so it can be loaded by the chart
+pub fn write_chart_config(helm: &Path, contracts_artifacts: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let config_dir = helm.join("config");
+    fs::create_dir_all(&config_dir)?;
+
+    let unzipped_artifacts = tempfile::TempDir::new()?;
+    zip_extract::extract(
+        File::open(contracts_artifacts)?,
+        unzipped_artifacts.path(),
+        true,
+    )?;
+
+    fs::copy(contracts_artifacts, config_dir.join("artifacts.zip"))?;
+    fs::copy(
+        unzipped_artifacts.path().join("addresses.json"),
+        config_dir.join("addresses.json"),
+    )?;
+
+    Ok(())
+}
+
+/// kubernetes deployments the chart creates for an artifact
+pub fn chart_deployments(kind: &ArtifactKind, release_name: &str) -> Vec<String> {
+    match kind {
+        ArtifactKind::Node | ArtifactKind::Geth => vec![
+            format!("{}-sequencer-deployment", kind.artifact_name()),
+            format!("{}-replica-deployment", kind.artifact_name()),
+        ],
+        ArtifactKind::Batcher | ArtifactKind::Proposer => vec![format!("{}-deployment", kind.artifact_name())],
+        // blockscout subchart names its deployment after the helm release
+        ArtifactKind::Explorer => vec![format!("{}-blockscout-stack-blockscout", release_name)],
+        ArtifactKind::Contracts => vec![],
+    }
+}
+
+/// whether the chart values at `helm` deploy the explorer
+pub fn explorer_enabled(helm: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    let values: serde_yaml::Value = serde_yaml::from_reader(File::open(helm.join("values.yaml"))?)?;
+
+    Ok(values["explorer"]["enabled"].as_bool().unwrap_or(true))
+}
+
+/// blockscout image of the deployment's op-explorer release as (repository, tag), None when the release has no
+/// op-explorer image, like those made before the explorer was an artifact, so the chart keeps the upstream one
+pub fn explorer_image(
+    deployment: &Deployment,
+    manifest: Option<&ReleaseManifest>,
+    release_registry: &dyn TReleaseRegistry,
+) -> Option<(String, String)> {
+    let release = Release::new(
+        ArtifactKind::Explorer.artifact_name().to_string(),
+        deployment
+            .release_for(ArtifactKind::Explorer.artifact_name())
+            .to_string(),
+        deployment.registry_url.clone(),
+    )
+    .pinned_by(manifest);
+
+    // our manifests list everything released under the name, only third-party releases need asking the registry
+    let released = match manifest {
+        Some(_) => release.digest.is_some(),
+        None => release_registry.exists(&release).unwrap_or_else(|e| {
+            warn!(
+                "Couldn't look up {}, keeping the upstream explorer: {}",
+                release.uri(),
+                e
+            );
+            false
+        }),
+    };
+    if !released {
+        return None;
+    }
+
+    // the blockscout subchart only takes a tag, a pinned image goes as tag@digest
+    let tag = match &release.digest {
+        Some(digest) => format!("{}@{}", release.artifact_tag, digest),
+        None => release.artifact_tag.clone(),
+    };
+
+    Some((
+        format!("{}/{}", release.registry_url, release.artifact_name),
+        tag,
+    ))
+}
+
+/// label selectors matching the pods the chart creates for a component
+pub fn chart_pod_selectors(component: &StackComponent, release_name: &str) -> Vec<String> {
+    match component {
+        StackComponent::Node => vec!["app in (op-node-sequencer, op-node-replica)".to_string()],
+        StackComponent::Geth => vec!["app in (op-geth-sequencer, op-geth-replica)".to_string()],
+        StackComponent::Batcher => vec!["app=op-batcher".to_string()],
+        StackComponent::Proposer => vec!["app=op-proposer".to_string()],
+        // blockscout pods carry the standard helm labels, our postgres doesn't. Grafana and prometheus share the
+        // instance label, the chart name tells blockscout apart
+        StackComponent::Explorer => vec![
+            format!(
+                "app.kubernetes.io/instance={},app.kubernetes.io/name=blockscout-stack",
+                release_name
+            ),
+            "app=blockscout-postgres".to_string(),
+        ],
+    }
+}
+
+/// Runs one `kubectl logs` per selector in parallel, calling `on_line` with the pod name and the log line
+pub fn stream_logs(
+    namespace: &str,
+    selectors: &[String],
+    options: &LogsOptions,
+    kubeconfig: Option<&Path>,
+    on_line: &(dyn Fn(&str, &str) + Sync),
+) -> Result<(), Box<dyn std::error::Error>> {
+    // boxed errors aren't Send, threads report them as strings
+    let results: Vec<Result<(), String>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = selectors
+            .iter()
+            .map(|selector| {
+                scope.spawn(move || {
+                    stream_selector_logs(namespace, selector, options, kubeconfig, on_line).map_err(|e| e.to_string())
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    for result in results {
+        result?;
+    }
+
+    Ok(())
+}
+
+fn stream_selector_logs(
+    namespace: &str,
+    selector: &str,
+    options: &LogsOptions,
+    kubeconfig: Option<&Path>,
+    on_line: &(dyn Fn(&str, &str) + Sync),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = Command::new("kubectl");
+    command
+        .arg("logs")
+        .arg("--namespace")
+        .arg(namespace)
+        .arg("--selector")
+        .arg(selector)
+        .arg("--all-containers")
+        .arg("--prefix")
+        .arg("--max-log-requests=20");
+
+    if options.follow {
+        command.arg("--follow");
+    }
+    // with a selector kubectl only prints the last 10 lines unless told otherwise
+    if let Some(since) = &options.since {
+        command.arg(format!("--since={}", since)).arg("--tail=-1");
+    }
+    if let Some(kubeconfig) = kubeconfig {
+        command.arg("--kubeconfig").arg(kubeconfig);
+    }
+
+    let mut child = command.stdout(Stdio::piped()).spawn()?;
+    for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+        let line = line?;
+        let (pod, message) = split_log_prefix(&line);
+        on_line(pod, message);
+    }
+
+    if !child.wait()?.success() {
+        return Err(format!("kubectl logs failed for pods matching {}", selector).into());
+    }
+
+    Ok(())
+}
+
+// kubectl --prefix lines look like [pod/<pod>/<container>] <message>
+fn split_log_prefix(line: &str) -> (&str, &str) {
+    match line
+        .strip_prefix("[pod/")
+        .and_then(|rest| rest.split_once("] "))
+    {
+        Some((prefix, message)) => (prefix.split('/').next().unwrap_or(prefix), message),
+        None => ("", line),
+    }
+}
+
+pub fn list_pods(namespace: &str, kubeconfig: Option<&Path>) -> Result<Vec<PodStatus>, Box<dyn std::error::Error>> {
+    let mut command = Command::new("kubectl");
+    command
+        .arg("get")
+        .arg("pods")
+        .arg("--namespace")
+        .arg(namespace)
+        .arg("--output")
+        .arg("json");
+
+    if let Some(kubeconfig) = kubeconfig {
+        command.arg("--kubeconfig").arg(kubeconfig);
+    }
+
+    let output = system::execute_command(&mut command, true)?;
+
+    parse_pods(&serde_json::from_str(&output)?)
+}
+
+fn parse_pods(pods: &Value) -> Result<Vec<PodStatus>, Box<dyn std::error::Error>> {
+    let items = pods["items"]
+        .as_array()
+        .ok_or("Unexpected kubectl get pods output")?;
+
+    Ok(items
+        .iter()
+        // pods of finished jobs, like the chain configuration ones, aren't part of what's running
+        .filter(|pod| pod["status"]["phase"].as_str() != Some("Succeeded"))
+        .map(|pod| {
+            let containers = pod["status"]["containerStatuses"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default();
+            let waiting_reason = containers
+                .iter()
+                .find_map(|c| c["state"]["waiting"]["reason"].as_str());
+
+            PodStatus {
+                name: pod["metadata"]["name"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                status: waiting_reason
+                    .or(pod["status"]["phase"].as_str())
+                    .unwrap_or("Unknown")
+                    .to_string(),
+                ready: !containers.is_empty()
+                    && containers
+                        .iter()
+                        .all(|c| c["ready"].as_bool() == Some(true)),
+                restarts: containers
+                    .iter()
+                    .filter_map(|c| c["restartCount"].as_u64())
+                    .sum(),
+            }
+        })
+        .collect())
+}
+
+pub fn wait_for_rollout(
+    namespace: &str,
+    deployment: &str,
+    kubeconfig: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = Command::new("kubectl");
+    command
+        .arg("rollout")
+        .arg("status")
+        .arg(format!("deployment/{}", deployment))
+        .arg("--namespace")
+        .arg(namespace)
+        .arg(format!("--timeout={}", ROLLOUT_TIMEOUT));
+
+    if let Some(kubeconfig) = kubeconfig {
+        command.arg("--kubeconfig").arg(kubeconfig);
+    }
+
+    system::execute_command(&mut command, false)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{explorer_image, parse_pods, split_log_prefix, PodStatus};
+    use crate::{
+        config::{AccountsConfig, NetworkConfig},
+        domain::{Deployment, MockTReleaseRegistry, ReleaseManifest, ReleasedArtifact},
+    };
+    use serde_json::json;
+
+    #[test]
+    fn should_split_kubectl_log_prefix() {
+        assert_eq!(
+            split_log_prefix("[pod/op-batcher-deployment-5d8f7/op-batcher] t=2024 lvl=info msg=\"Started\""),
+            (
+                "op-batcher-deployment-5d8f7",
+                "t=2024 lvl=info msg=\"Started\""
+            )
+        );
+        assert_eq!(split_log_prefix("unprefixed line"), ("", "unprefixed line"));
+    }
+
+    #[test]
+    fn should_parse_pods_status() {
+        let pods = json!({
+            "items": [
+                {
+                    "metadata": { "name": "op-batcher-deployment-5d8f7" },
+                    "status": {
+                        "phase": "Running",
+                        "containerStatuses": [{ "ready": true, "restartCount": 1, "state": { "running": {} } }]
+                    }
+                },
+                {
+                    "metadata": { "name": "op-node-sequencer-deployment-9c2a1" },
+                    "status": {
+                        "phase": "Running",
+                        "containerStatuses": [
+                            { "ready": false, "restartCount": 3, "state": { "waiting": { "reason": "CrashLoopBackOff" } } }
+                        ]
+                    }
+                },
+                {
+                    "metadata": { "name": "opraas-config-job-x7k2p" },
+                    "status": {
+                        "phase": "Succeeded",
+                        "containerStatuses": [
+                            { "ready": false, "restartCount": 0, "state": { "terminated": { "reason": "Completed" } } }
+                        ]
+                    }
+                }
+            ]
+        });
+
+        assert_eq!(
+            parse_pods(&pods).unwrap(),
+            vec![
+                PodStatus {
+                    name: "op-batcher-deployment-5d8f7".to_string(),
+                    status: "Running".to_string(),
+                    ready: true,
+                    restarts: 1,
+                },
+                PodStatus {
+                    name: "op-node-sequencer-deployment-9c2a1".to_string(),
+                    status: "CrashLoopBackOff".to_string(),
+                    ready: false,
+                    restarts: 3,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_only_point_the_explorer_at_released_images() {
+        let deployment = Deployment::new(
+            "dev".to_string(),
+            "v0.1.0".to_string(),
+            "wakeuplabs".to_string(),
+            NetworkConfig::null(),
+            AccountsConfig::null(),
+        );
+        let released = |artifact_name: &str| ReleasedArtifact {
+            artifact_name: artifact_name.to_string(),
+            registry_url: "wakeuplabs".to_string(),
+            digest: "sha256:abc".to_string(),
+            platforms: vec![],
+            source_repo: String::new(),
+            commit: String::new(),
+            built_at: 0,
+        };
+
+        // our releases are looked up in their manifest, without asking the registry
+        let mut manifest = ReleaseManifest::new("v0.1.0");
+        manifest.upsert(released("op-node"));
+        assert_eq!(
+            explorer_image(&deployment, Some(&manifest), &MockTReleaseRegistry::new()),
+            None
+        );
+
+        manifest.upsert(released("op-explorer"));
+        assert_eq!(
+            explorer_image(&deployment, Some(&manifest), &MockTReleaseRegistry::new()),
+            Some((
+                "wakeuplabs/op-explorer".to_string(),
+                "v0.1.0@sha256:abc".to_string()
+            ))
+        );
+
+        // third-party ones in the registry
+        let mut release_registry = MockTReleaseRegistry::new();
+        release_registry
+            .expect_exists()
+            .times(1)
+            .returning(|_| Ok(false));
+        assert_eq!(explorer_image(&deployment, None, &release_registry), None);
+
+        let mut release_registry = MockTReleaseRegistry::new();
+        release_registry
+            .expect_exists()
+            .times(1)
+            .returning(|_| Err("unauthorized".into()));
+        assert_eq!(explorer_image(&deployment, None, &release_registry), None);
+    }
+}
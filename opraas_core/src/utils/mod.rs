@@ -1,3 +1,5 @@
+pub mod docker;
 pub mod git;
+pub mod helm;
 pub mod system;
 pub mod yaml;
@@ -6,7 +6,7 @@ use axum::{
 };
 use opraas_core::{
     application::{CreateProjectService, TCreateProjectService},
-    config::{AccountsConfig, ArtifactsConfig, CoreConfig, NetworkConfig},
+    config::{AccountsConfig, ArtifactsConfig, ConfigValidator, CoreConfig, NetworkConfig, TConfigValidator},
 };
 use serde::Deserialize;
 use std::{path::PathBuf, sync::Arc};
@@ -63,7 +63,7 @@ pub struct Payload {
 pub async fn build_handler(
     Extension(create_service): Extension<Arc<CreateProjectService>>,
     Json(data): Json<Payload>,
-) -> Result<impl IntoResponse, (StatusCode, &'static str)> {
+) -> Result<impl IntoResponse, (StatusCode, String)> {
     let mut headers = HeaderMap::new();
     headers.insert("Content-Type", HeaderValue::from_static("application/zip"));
     headers.insert(
@@ -123,13 +123,17 @@ pub async fn build_handler(
         artifacts: ArtifactsConfig::null(),
     };
 
+    ConfigValidator::new()
+        .validate(&config)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
     let tmp_dir = TempDir::new().unwrap(); // automatically clean up on drop
     let project = create_service
         .create(&PathBuf::from(tmp_dir.path()), &config, false)
         .unwrap();
 
     let zip_buffer =
-        zip_folder(&project.root).map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to zip project"));
+        zip_folder(&project.root).map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to zip project".to_string()));
 
     Ok((StatusCode::OK, headers, zip_buffer))
 }
@@ -37,7 +37,7 @@ function ConfigureChain() {
       sequencer_window_size: 3600,
       channel_timeout: 300,
       system_config_start_block: 0,
-      batch_inbox_address: "0xff69000000000000000000000000001201101712",
+      batch_inbox_address: "0xfF69000000000000000000000000001201101712",
 
       finalization_period_seconds: 12,
 